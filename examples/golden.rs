@@ -0,0 +1,32 @@
+extern crate gaugen;
+
+use std::env;
+
+// Renders the readme layout offscreen and compares it against the reference images in
+// resources/golden. Run with "--bless" to (re)generate the references.
+fn main() {
+    let bless = env::args().any(|arg| arg == "--bless");
+
+    gaugen::session::SessionBuilder::new()
+        .register_components(gaugen::basic_components::components())
+        .register_components(gaugen::geometry_components::components())
+        .init_headless(400, 400, |session: &mut gaugen::headless::HeadlessSession| {
+            let test = gaugen::golden::GoldenTest::new(
+                "readme",
+                "resources/screen_readme.json",
+                "resources/golden",
+            )
+            .frames_from_file("resources/golden/readme_frames.json")
+            .expect("Failed to load frames")
+            .update_references(bless);
+
+            match test.run(session, &gaugen::frontend::DarkPalette {}) {
+                Ok(()) => println!("readme: ok"),
+                Err(er) => {
+                    println!("readme: FAILED\n{}", er);
+                    std::process::exit(1);
+                }
+            }
+        })
+        .expect("Failed to load the resources");
+}
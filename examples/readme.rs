@@ -25,5 +25,6 @@ fn main() {
                     break; // handle window being closed, etc.
                 }
             }
-        });
+        })
+        .expect("Failed to load the resources");
 }
@@ -5,6 +5,7 @@ extern crate nalgebra as na;
 extern crate nanovg;
 extern crate rand;
 
+use gaugen::basic_components::RotationalIndicatorData;
 use std::collections::HashMap;
 use std::net;
 use std::time::Duration;
@@ -18,6 +19,18 @@ fn bytes_to_u32(array: &[u8]) -> u32 {
         + ((array[3] as u32) << 24)
 }
 
+// engine cluster gauge name, x-plane (group, index) and scale
+const ENGINE_GAUGES: [(&str, (u32, u32), f32); 8] = [
+    ("e11", (34, 0), 0.001),
+    ("e21", (34, 1), 0.001),
+    ("e12", (46, 0), 1.0),
+    ("e22", (46, 1), 1.0),
+    ("e13", (37, 0), 1.0),
+    ("e23", (37, 1), 1.0),
+    ("e14", (45, 0), 1.0),
+    ("e24", (45, 1), 1.0),
+];
+
 const XPLANE_UNIT_SIZE: usize = 4;
 const XPLANE_UNITS_PER_PACKET: usize = 8;
 
@@ -53,6 +66,32 @@ fn listen(socket: &net::UdpSocket) -> Option<HashMap<(u32, u32), f32>> {
     Some(ret)
 }
 
+// x-plane listens for commands on this port, see "Data Output" in its settings
+const XPLANE_COMMAND_ADDRESS: &str = "127.0.0.1:49000";
+
+fn send_command(socket: &net::UdpSocket, command: &str) {
+    let mut packet = b"CMND\0".to_vec();
+    packet.extend_from_slice(command.as_bytes());
+    packet.push(0);
+
+    match socket.send_to(&packet, XPLANE_COMMAND_ADDRESS) {
+        Ok(_) => {}
+        Err(er) => println!("Failed to send command {}: {}", command, er),
+    }
+}
+
+// the opposite direction of md71_telemetry_to_hooks
+fn forward_events(socket: &net::UdpSocket, events: Vec<(String, gaugen::input::OutputEvent)>) {
+    for (name, event) in events {
+        match (name.as_str(), event) {
+            ("gear", gaugen::input::OutputEvent::Clicked) => {
+                send_command(socket, "sim/flight_controls/landing_gear_toggle")
+            }
+            _ => {}
+        }
+    }
+}
+
 fn md71_telemetry_to_hooks(telemetry: HashMap<(u32, u32), f32>) -> gaugen::Hooks {
     let mut hooks = HashMap::new();
 
@@ -95,43 +134,74 @@ fn md71_telemetry_to_hooks(telemetry: HashMap<(u32, u32), f32>) -> gaugen::Hooks
     );
     gaugen::add_hook(&mut hooks, "ssi", "yaw", telemetry[&(17, 2)] / 180.0 * 3.14);
 
-    gaugen::add_hook(&mut hooks, "e11", "value", telemetry[&(34, 0)] / 1000.0);
-    gaugen::add_hook(&mut hooks, "e21", "value", telemetry[&(34, 1)] / 1000.0);
-    gaugen::add_hook(&mut hooks, "e12", "value", telemetry[&(46, 0)]);
-    gaugen::add_hook(&mut hooks, "e22", "value", telemetry[&(46, 1)]);
-    gaugen::add_hook(&mut hooks, "e13", "value", telemetry[&(37, 0)]);
-    gaugen::add_hook(&mut hooks, "e23", "value", telemetry[&(37, 1)]);
-    gaugen::add_hook(&mut hooks, "e14", "value", telemetry[&(45, 0)]);
-    gaugen::add_hook(&mut hooks, "e24", "value", telemetry[&(45, 1)]);
-
     hooks
 }
 
 fn main() {
     let socket = net::UdpSocket::bind("127.0.0.1:6112").expect("failed to bind host socket");
     socket
-        .set_read_timeout(Some(Duration::new(1, 0)))
-        .expect("failed set socket read timeout");
+        .set_nonblocking(true)
+        .expect("failed to make host socket non-blocking");
 
     gaugen::session::SessionBuilder::new()
         .register_components(gaugen::basic_components::components())
         .register_components(gaugen::geometry_components::components())
         .init(|session: &mut gaugen::session::Session| {
-            let mut view = session.new_view("resources/screen_xplane11.json");
-            loop {
-                let hooks = match listen(&socket) {
-                    Some(telemetry) => md71_telemetry_to_hooks(telemetry),
-                    None => HashMap::new(),
-                };
-
-                match view {
-                    Some(ref mut view) => {
-                        if !session.draw(view, &gaugen::frontend::DarkPalette {}, &hooks) {
-                            break;
-                        }
-                    }
-                    _ => {}
-                };
+            let mut view = match session.new_view("resources/screen_xplane11.json") {
+                Ok(view) => view,
+                Err(er) => {
+                    println!("Failed to initialize view: {}", er);
+                    return;
+                }
+            };
+
+            // updated through typed handles, no JSON involved
+            let engine_gauges: Vec<_> = ENGINE_GAUGES
+                .iter()
+                .map(|(name, source, scale)| {
+                    let instance = view
+                        .instance::<RotationalIndicatorData>(name)
+                        .expect("Engine gauge missing in the layout");
+                    (instance, *source, *scale)
+                })
+                .collect();
+
+            // cross out the primary gauges when the simulator stops sending data
+            for name in &["speed", "alt"] {
+                view.set_stale_timeout(name, "value", Some(Duration::new(2, 0)));
             }
-        });
+
+            session.set_target_fps(Some(30.0)).unwrap();
+            session.set_redraw_on_change(true);
+
+            let stats = session.run(|session, _delta| {
+                // telemetry is retained by the view, frames without a packet keep the last values
+                loop {
+                    let telemetry = match listen(&socket) {
+                        Some(telemetry) => telemetry,
+                        None => break,
+                    };
+
+                    for (instance, source, scale) in &engine_gauges {
+                        instance.update(|data| data.value = telemetry[source] * scale);
+                    }
+
+                    match view.set_hooks(&md71_telemetry_to_hooks(telemetry)) {
+                        Ok(()) => {}
+                        Err(er) => println!("Rejected telemetry: {}", er),
+                    }
+                }
+
+                if !session.draw(&mut view, &gaugen::frontend::DarkPalette {}, &HashMap::new()) {
+                    return false;
+                }
+
+                forward_events(&socket, view.drain_events());
+
+                true
+            });
+
+            println!("{}", stats);
+        })
+        .expect("Failed to load the resources");
 }
@@ -44,11 +44,27 @@ fn format_float(v: f32, decimals: u32) -> String {
     ret
 }
 
+// Status of the range the value falls into, Error outside of all ranges. Ranges are given
+// by their upper ends, the first one starts at value_min; shared by the indicators.
+fn range_status(value: f32, value_min: f32, value_ranges: &[(f32, Status)]) -> Status {
+    let mut last_range_end = value_min;
+
+    for range_end in value_ranges {
+        if value >= last_range_end && value < range_end.0 {
+            return range_end.1;
+        }
+
+        last_range_end = range_end.0;
+    }
+
+    Status::Error
+}
+
 // =========================== ROTATIONAL INDICATOR ===========================
 
 pub struct RotationalIndicator {}
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub struct RotationalIndicatorData {
     pub precision: u32,
     pub unit: String,
@@ -56,6 +72,26 @@ pub struct RotationalIndicatorData {
     pub value: f32,
     pub value_min: f32,
     pub value_ranges: Vec<(f32, Status)>,
+    pub caption_font: Option<resources::FontSelection>,
+    pub value_font: Option<resources::FontSelection>, // a monospace one keeps digits still
+}
+
+impl RotationalIndicatorData {
+    fn normalize(&self, value: f32) -> f32 {
+        let value_max = self.value_ranges[self.value_ranges.len() - 1].0;
+
+        if value < self.value_min {
+            0.001
+        } else if value > value_max {
+            1.0
+        } else {
+            (value - self.value_min) / (value_max - self.value_min)
+        }
+    }
+
+    fn status(&self) -> Status {
+        range_status(self.value, self.value_min, &self.value_ranges)
+    }
 }
 
 impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
@@ -72,17 +108,7 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
         let base_thickness = base_radius / 10.0;
         let ymo = base_radius / -5.5; //y middle offset
 
-        let value_max = data.value_ranges[data.value_ranges.len() - 1].0;
-
-        let normalize = |value: f32| {
-            if value < data.value_min {
-                0.001
-            } else if value > value_max {
-                1.0
-            } else {
-                (value - data.value_min) / (value_max - data.value_min)
-            }
-        };
+        let normalize = |value: f32| data.normalize(value);
 
         let smartarc = |p0: f32,
                         p1: f32,
@@ -117,7 +143,7 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
 
         let mut last_range_end = normalize(data.value_min);
 
-        let mut value_status = Status::Error;
+        let value_status = data.status();
 
         let nvalue = normalize(data.value);
 
@@ -132,10 +158,6 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
                 Color::from_rgba(0, 0, 0, 0),
             );
 
-            if nvalue >= last_range_end && nvalue < current_range_end {
-                value_status = range_end.1;
-            }
-
             last_range_end = current_range_end;
         }
 
@@ -144,7 +166,7 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
             1.0,
             base_radius * 1.09,
             0.0,
-            Color::from_rgba(160, 160, 160, 255),
+            ctx.resources.palette.scale_color(),
             ctx.resources.palette.status_to_color_bg(Status::Ok),
         );
 
@@ -154,13 +176,13 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
                 nvalue,
                 base_radius,
                 base_thickness * 1.75,
-                Color::from_rgba(255, 255, 255, 255),
+                ctx.resources.palette.needle_color(),
                 Color::from_rgba(0, 0, 0, 0),
             );
         }
 
         let text_opts_caption = TextOptions {
-            color: Color::from_rgba(180, 180, 180, 255),
+            color: ctx.resources.palette.caption_color(),
             size: base_radius / 2.0,
             align: Alignment::new().center().middle(),
             line_height: base_radius / 3.0,
@@ -180,14 +202,14 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
         if value_status != Status::Error || ctx.time * 2.0 - ((ctx.time * 2.0) as i32 as f32) < 0.66
         {
             ctx.frame.text_box(
-                ctx.resources.font,
+                ctx.resources.select_font(&data.caption_font),
                 (zone.left(), zone.m.y + base_radius / 1.5 - ymo),
                 &data.caption,
                 text_opts_caption,
             );
         }
         ctx.frame.text_box(
-            ctx.resources.font,
+            ctx.resources.select_font(&data.value_font),
             (zone.left(), zone.m.y - base_radius / 10.0 - ymo),
             format_float(data.value, data.precision) + &data.unit,
             text_opts_value,
@@ -202,6 +224,11 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
         
     }
 
+    // the caption blinks while the value is out of range
+    fn is_animated(&self, __internal_data: &(), data: &RotationalIndicatorData) -> bool {
+        data.status() == Status::Error
+    }
+
     fn get_default_data(&self) -> Option<RotationalIndicatorData> {
         Some(RotationalIndicatorData {
             precision: 1,
@@ -210,9 +237,15 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
             value: 50.0,
             value_min: 0.0,
             value_ranges: vec![(100.0, Status::Ok)],
+            caption_font: None,
+            value_font: None,
         })
     }
 
+    fn resources(&self, data: &RotationalIndicatorData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.caption_font, &data.value_font])
+    }
+
     fn max_children(&self) -> Option<u32> {
         Some(0)
     }
@@ -226,11 +259,12 @@ impl Component<RotationalIndicatorData, ()> for RotationalIndicator {
 
 pub struct TextField {}
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub struct TextFieldData {
     pub text: String,
-    pub front_color: SerializableColor,
-    pub back_color: SerializableColor,
+    pub front_color: Option<SerializableColor>, // the palette's caption color by default
+    pub back_color: Option<SerializableColor>,  // the palette's Ok background by default
+    pub font: Option<resources::FontSelection>,
 }
 
 impl Component<TextFieldData, f32> for TextField {
@@ -243,16 +277,27 @@ impl Component<TextFieldData, f32> for TextField {
         data: &TextFieldData,
     ) {
         let zone = zone.constraint_to_aspect(Some(*aspect));
+        let palette = ctx.resources.palette;
+
+        let front_color = match &data.front_color {
+            Some(color) => ctx.resources.dim(color.color),
+            None => palette.caption_color(),
+        };
+
+        let back_color = match &data.back_color {
+            Some(color) => ctx.resources.dim(color.color),
+            None => palette.status_to_color_bg(Status::Ok),
+        };
 
         ctx.frame.path(
             |mut path| {
                 path.rect((zone.left(), zone.bottom()), (zone.size.x, zone.size.y));
-                path.fill(data.back_color.color, Default::default());
+                path.fill(back_color, Default::default());
             },
             Default::default(),
         );
         let text_opts = TextOptions {
-            color: data.front_color.color,
+            color: front_color,
             size: zone.size.y * 1.0,
             align: Alignment::new().center().middle(),
             line_height: zone.size.y * 1.0,
@@ -261,7 +306,7 @@ impl Component<TextFieldData, f32> for TextField {
         };
 
         ctx.frame.text_box(
-            ctx.resources.font,
+            ctx.resources.select_font(&data.font),
             (zone.left(), zone.m.y),
             &data.text,
             text_opts,
@@ -274,7 +319,7 @@ impl Component<TextFieldData, f32> for TextField {
         data: &TextFieldData
     ) -> f32{
         let bounds = ctx.frame.text_box_bounds(
-            ctx.resources.font,
+            ctx.resources.select_font(&data.font),
             (0.0, 0.0),
             data.text.as_str(),
             nanovg::TextOptions::default(),
@@ -286,18 +331,37 @@ impl Component<TextFieldData, f32> for TextField {
         w / h
     }
 
+    fn handle_event(
+        &self,
+        event: &input::InputEvent,
+        zone: DrawZone,
+        aspect: &mut f32,
+        __data: &TextFieldData,
+        emit: &mut dyn FnMut(input::OutputEvent),
+    ) {
+        match event {
+            input::InputEvent::PointerUp(p, input::PointerButton::Left) => {
+                if zone.constraint_to_aspect(Some(*aspect)).contains(*p) {
+                    emit(input::OutputEvent::Clicked);
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn get_default_data(&self) -> Option<TextFieldData> {
         Some(TextFieldData {
             text: "<Placeholder>".to_string(),
-            front_color: SerializableColor {
-                color: Color::from_rgb(0x80, 0x80, 0x80),
-            },
-            back_color: SerializableColor {
-                color: Color::from_rgb(0x0, 0x0, 0x60),
-            },
+            front_color: None,
+            back_color: None,
+            font: None,
         })
     }
 
+    fn resources(&self, data: &TextFieldData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
+
     fn max_children(&self) -> Option<u32> {
         Some(0)
     }
@@ -309,16 +373,32 @@ impl Component<TextFieldData, f32> for TextField {
 
 // =========================== SPATIAL SITUATION INDICATOR ===========================
 
-struct SpatialSituationIndicator {}
-
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
-struct SpatialSituationIndicatorData {
-    projection_zoom: f32,
-    yaw: f32,
-    pitch: f32,
-    roll: f32,
+pub struct SpatialSituationIndicator {}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct SpatialSituationIndicatorData {
+    pub projection_zoom: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    pub font: Option<resources::FontSelection>, // of the ladder labels
+    pub sky_ground: bool, // filled sky and ground with a roll scale under the ladders
+    pub slip: f32,        // deflection of the slip/skid indicator, -1.0 to 1.0
+    pub flight_path_angle: f32, // radians, like the attitude
+    pub drift: f32,             // radians, positive to the right of the heading
+    pub fd_pitch: f32,          // commanded pitch, radians
+    pub fd_roll: f32,           // commanded roll, radians
+    pub fpv_visible: bool,
+    pub fd_visible: bool,
 }
 
+// radius of the area inside the inner ring, relative to the zone's size
+const SSI_DISPLAY_RADIUS: f32 = 0.45;
+const SSI_HORIZON_SAMPLES: usize = 180;
+const SSI_BANK_TICKS: [f32; 5] = [10.0, 20.0, 30.0, 45.0, 60.0];
+// roll error (degrees) moving the flight director's vertical bar to the end of its travel
+const SSI_FD_ROLL_FULL_SCALE: f32 = 30.0;
+
 trait DegreeRadConversions {
     fn rad(&self) -> f32;
     fn deg(&self) -> f32;
@@ -395,6 +475,7 @@ impl SpatialSituationIndicator {
     pub fn draw_text(
         &self,
         ctx: &mut PresentationContext,
+        font: nanovg::Font,
         zone: &DrawZone,
         o: &nalgebra::UnitQuaternion<f32>,
         zoom: f32,
@@ -403,7 +484,7 @@ impl SpatialSituationIndicator {
     ) {
         let linelen = zone.size.y / 2.5;
         let text_opts_value = TextOptions {
-            color: Color::from_rgba(255, 255, 255, 255),
+            color: ctx.resources.palette.ladder_label_color(),
             size: zone.size.y / 20.0,
             align: Alignment::new().center().middle(),
             line_height: zone.size.y / 20.0,
@@ -413,7 +494,7 @@ impl SpatialSituationIndicator {
 
         match self.projection(p, o, zoom, 0.85) {
             Some(tp) => ctx.frame.text_box(
-                ctx.resources.font,
+                font,
                 (
                     tp.x * zone.size.x + zone.m.x - linelen / 2.0,
                     tp.y * zone.size.y + zone.m.y,
@@ -425,6 +506,306 @@ impl SpatialSituationIndicator {
         }
     }
 
+    // point of the display (relative to the zone's middle, in zone sizes) back on the sphere
+    fn unproject(&self, p: Vector2<f32>, zoom: f32) -> Vector3<f32> {
+        let y = p.x * 2.0 / zoom;
+        let z = p.y * 2.0 / zoom;
+
+        Vector3::new((1.0 - y * y - z * z).max(0.0).sqrt(), y, z)
+    }
+
+    // screen angle of the direction to the sky, clockwise from up
+    fn sky_angle(&self, o: &nalgebra::UnitQuaternion<f32>) -> f32 {
+        let up = o.inverse() * Vector3::new(0.0, 0.0, -1.0);
+
+        up.y.atan2(-up.z)
+    }
+
+    pub fn draw_sky_ground(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: &DrawZone,
+        o: &nalgebra::UnitQuaternion<f32>,
+        zoom: f32,
+    ) {
+        let palette = ctx.resources.palette;
+        let down = o.inverse() * Vector3::new(0.0, 0.0, 1.0);
+        let is_ground = |p: Vector2<f32>| self.unproject(p, zoom).dot(&down) > 0.0;
+        let to_screen =
+            |p: Vector2<f32>| (p.x * zone.size.x + zone.m.x, p.y * zone.size.y + zone.m.y);
+        let rim = |angle: f32| Vector2::new(angle.cos(), angle.sin()) * SSI_DISPLAY_RADIUS;
+
+        ctx.frame.path(
+            |mut path| {
+                path.circle((zone.m.x, zone.m.y), SSI_DISPLAY_RADIUS * zone.size.x);
+                path.fill(
+                    match is_ground(Vector2::new(0.0, 0.0)) {
+                        true => palette.ground_color(),
+                        false => palette.sky_color(),
+                    },
+                    Default::default(),
+                );
+            },
+            Default::default(),
+        );
+
+        // visible part of the horizon, continuous as the display is a cap of the sphere
+        let horizon: Vec<Option<Vector2<f32>>> = (0..SSI_HORIZON_SAMPLES)
+            .map(|i| {
+                let yaw = i as f32 * 360.0 / SSI_HORIZON_SAMPLES as f32;
+                self.projection(Vector2::new(yaw, 0.0), o, zoom, 1.0)
+            })
+            .collect();
+
+        let hidden = match horizon.iter().position(|p| p.is_none()) {
+            Some(hidden) => hidden,
+            None => return,
+        };
+
+        let line: Vec<Vector2<f32>> = (1..=SSI_HORIZON_SAMPLES)
+            .map(|i| horizon[(hidden + i) % SSI_HORIZON_SAMPLES])
+            .skip_while(|p| p.is_none())
+            .take_while(|p| p.is_some())
+            .flatten()
+            .collect();
+
+        if line.len() < 2 {
+            return;
+        }
+
+        // the ground is closed along the rim of the display, on the side that is ground
+        let first = line[0];
+        let last = line[line.len() - 1];
+        let from = last.y.atan2(last.x);
+        let ccw = (first.y.atan2(first.x) - from).rem_euclid(2.0 * consts::PI);
+        let sweep = match is_ground(rim(from + ccw / 2.0) * 0.99) {
+            true => ccw,
+            false => ccw - 2.0 * consts::PI,
+        };
+        let rim_steps = (sweep.abs() / consts::PI * 64.0).ceil() as usize + 1;
+
+        ctx.frame.path(
+            |mut path| {
+                path.move_to(to_screen(first));
+
+                for p in &line[1..] {
+                    path.line_to(to_screen(*p));
+                }
+
+                for i in 0..=rim_steps {
+                    path.line_to(to_screen(rim(from + sweep * i as f32 / rim_steps as f32)));
+                }
+
+                path.close();
+                path.fill(palette.ground_color(), Default::default());
+            },
+            Default::default(),
+        );
+
+        ctx.frame.path(
+            |mut path| {
+                path.move_to(to_screen(first));
+
+                for p in &line[1..] {
+                    path.line_to(to_screen(*p));
+                }
+
+                path.stroke(
+                    palette.horizon_color(),
+                    StrokeOptions {
+                        width: 2.0,
+                        ..Default::default()
+                    },
+                );
+            },
+            Default::default(),
+        );
+    }
+
+    // Fixed bank scale over the top of the display, with a sky pointer turning with the
+    // horizon and the slip/skid indicator under it.
+    pub fn draw_roll_scale(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: &DrawZone,
+        o: &nalgebra::UnitQuaternion<f32>,
+        slip: f32,
+    ) {
+        let palette = ctx.resources.palette;
+        let radius = SSI_DISPLAY_RADIUS * 0.9 * zone.size.x;
+        let unit = zone.size.x / 40.0;
+        let up = -consts::PI / 2.0;
+        let max_bank = SSI_BANK_TICKS[SSI_BANK_TICKS.len() - 1];
+
+        let stroke_opts = StrokeOptions {
+            width: 2.0,
+            ..Default::default()
+        };
+
+        ctx.frame.path(
+            |mut path| {
+                path.arc(
+                    (zone.m.x, zone.m.y),
+                    radius,
+                    up - max_bank.rad(),
+                    up + max_bank.rad(),
+                    Winding::Direction(Direction::Clockwise),
+                );
+
+                for bank in SSI_BANK_TICKS.iter() {
+                    let length = match *bank == 30.0 || *bank == 60.0 {
+                        true => 1.5 * unit,
+                        false => 0.8 * unit,
+                    };
+
+                    for angle in [up - bank.rad(), up + bank.rad()].iter() {
+                        let (sin, cos) = angle.sin_cos();
+                        path.move_to((zone.m.x + cos * radius, zone.m.y + sin * radius));
+                        path.line_to((
+                            zone.m.x + cos * (radius + length),
+                            zone.m.y + sin * (radius + length),
+                        ));
+                    }
+                }
+
+                path.stroke(palette.scale_color(), stroke_opts);
+            },
+            Default::default(),
+        );
+
+        // zero bank index
+        ctx.frame.path(
+            |mut path| {
+                path.move_to((zone.m.x, zone.m.y - radius));
+                path.line_to((zone.m.x - 0.6 * unit, zone.m.y - radius - unit));
+                path.line_to((zone.m.x + 0.6 * unit, zone.m.y - radius - unit));
+                path.close();
+                path.fill(palette.scale_color(), Default::default());
+            },
+            Default::default(),
+        );
+
+        let pointer_opts = nanovg::PathOptions {
+            transform: Some(
+                nanovg::Transform::new()
+                    .translate(zone.m.x, zone.m.y)
+                    .rotate(self.sky_angle(o)),
+            ),
+            ..Default::default()
+        };
+
+        ctx.frame.path(
+            |mut path| {
+                path.move_to((0.0, -radius));
+                path.line_to((-0.6 * unit, -radius + unit));
+                path.line_to((0.6 * unit, -radius + unit));
+                path.close();
+                path.fill(palette.needle_color(), Default::default());
+            },
+            pointer_opts,
+        );
+
+        let slip = slip.max(-1.0).min(1.0) * 1.5 * unit;
+
+        ctx.frame.path(
+            |mut path| {
+                path.rect(
+                    (slip - 0.6 * unit, -radius + 1.2 * unit),
+                    (1.2 * unit, 0.4 * unit),
+                );
+                path.fill(palette.needle_color(), Default::default());
+            },
+            pointer_opts,
+        );
+    }
+
+    // Where the aircraft is actually going, at the flight path angle and drift off the
+    // heading. Hidden while outside of the display.
+    pub fn draw_flight_path_vector(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: &DrawZone,
+        o: &nalgebra::UnitQuaternion<f32>,
+        data: &SpatialSituationIndicatorData,
+    ) {
+        let direction = Vector2::new(
+            (data.yaw + data.drift).deg(),
+            data.flight_path_angle.deg(),
+        );
+
+        let p = match self.projection(direction, o, data.projection_zoom, 0.9) {
+            Some(p) => Vector2::new(p.x * zone.size.x + zone.m.x, p.y * zone.size.y + zone.m.y),
+            None => return,
+        };
+
+        let unit = zone.size.y / 40.0;
+
+        ctx.frame.path(
+            |mut path| {
+                path.circle((p.x, p.y), unit);
+                path.move_to((p.x - unit, p.y));
+                path.line_to((p.x - 2.5 * unit, p.y));
+                path.move_to((p.x + unit, p.y));
+                path.line_to((p.x + 2.5 * unit, p.y));
+                path.move_to((p.x, p.y - unit));
+                path.line_to((p.x, p.y - 2.0 * unit));
+                path.stroke(
+                    ctx.resources.palette.symbology_color(),
+                    StrokeOptions {
+                        width: 2.0,
+                        ..Default::default()
+                    },
+                );
+            },
+            Default::default(),
+        );
+    }
+
+    // Command bars: the horizontal one at the commanded pitch on the ladder straight ahead,
+    // the vertical one off the middle by the roll error. Both stop at the display's edge.
+    pub fn draw_flight_director(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: &DrawZone,
+        o: &nalgebra::UnitQuaternion<f32>,
+        data: &SpatialSituationIndicatorData,
+    ) {
+        let travel = SSI_DISPLAY_RADIUS * 0.6;
+        let bar = SSI_DISPLAY_RADIUS * 0.5;
+
+        let command = Vector2::new(data.yaw.deg(), data.fd_pitch.deg());
+        let pitch_offset = match self.projection(command, o, data.projection_zoom, 0.9) {
+            Some(p) => p.y.max(-travel).min(travel),
+            None => match data.fd_pitch > data.pitch {
+                true => -travel,
+                false => travel,
+            },
+        };
+
+        let roll_error = (data.fd_roll - data.roll).deg() / SSI_FD_ROLL_FULL_SCALE;
+        let roll_offset = roll_error.max(-1.0).min(1.0) * travel;
+
+        let y = pitch_offset * zone.size.y + zone.m.y;
+        let x = roll_offset * zone.size.x + zone.m.x;
+
+        ctx.frame.path(
+            |mut path| {
+                path.move_to((zone.m.x - bar * zone.size.x, y));
+                path.line_to((zone.m.x + bar * zone.size.x, y));
+                path.move_to((x, zone.m.y - bar * zone.size.y));
+                path.line_to((x, zone.m.y + bar * zone.size.y));
+                path.stroke(
+                    ctx.resources.palette.symbology_color(),
+                    StrokeOptions {
+                        width: 3.0,
+                        ..Default::default()
+                    },
+                );
+            },
+            Default::default(),
+        );
+    }
+
     pub fn draw_ffd(&self, ctx: &mut PresentationContext, zone: &DrawZone) {
         let unit = zone.size.y / 20.0;
         ctx.frame.path(
@@ -437,7 +818,7 @@ impl SpatialSituationIndicator {
 
                 path.circle((zone.m.x, zone.m.y), 1.0);
                 path.stroke(
-                    Color::from_rgba(0xff, 0xff, 0x20, 0xa2),
+                    ctx.resources.palette.symbology_color(),
                     StrokeOptions {
                         width: 3.0,
                         ..Default::default()
@@ -464,8 +845,21 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
             yaw: 0.0,
             pitch: 0.0,
             roll: 0.0,
+            font: None,
+            sky_ground: false,
+            slip: 0.0,
+            flight_path_angle: 0.0,
+            drift: 0.0,
+            fd_pitch: 0.0,
+            fd_roll: 0.0,
+            fpv_visible: false,
+            fd_visible: false,
         })
     }
+
+    fn resources(&self, data: &SpatialSituationIndicatorData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
     fn init_instance(
         &self,
         __ctx: &mut PresentationContext,
@@ -486,12 +880,19 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
         let orientation_quat =
             UnitQuaternion::from_euler_angles(public_data.roll, public_data.pitch, public_data.yaw);
 
+        let font = ctx.resources.select_font(&public_data.font);
+        let ladder_color = ctx.resources.palette.ladder_color();
+
+        if public_data.sky_ground {
+            self.draw_sky_ground(ctx, &zone, &orientation_quat, public_data.projection_zoom);
+        }
+
         ctx.frame.path(
             |mut path| {
                 path.circle((zone.m.x, zone.m.y), 1.0 * zone.size.x / 2.0);
                 path.circle((zone.m.x, zone.m.y), 0.9 * zone.size.x / 2.0);
                 path.stroke(
-                    Color::from_rgba(0xa0, 0xa0, 0xa0, 0xa0),
+                    ctx.resources.palette.scale_color(),
                     StrokeOptions {
                         width: 3.0,
                         ..Default::default()
@@ -521,7 +922,7 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
                 p2,
                 |path| {
                     path.stroke(
-                        Color::from_rgba(0x50, 0x50, 0x50, 0xff),
+                        ladder_color,
                         StrokeOptions {
                             width: 1.5,
                             ..Default::default()
@@ -533,6 +934,7 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
             if i != 0 && i * 5 <= 90 {
                 self.draw_text(
                     ctx,
+                    font,
                     &zone,
                     &orientation_quat,
                     public_data.projection_zoom,
@@ -568,7 +970,7 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
                 p2,
                 |path| {
                     path.stroke(
-                        Color::from_rgba(0x50, 0x50, 0x50, 0xff),
+                        ladder_color,
                         StrokeOptions {
                             width: 1.5,
                             ..Default::default()
@@ -579,6 +981,7 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
 
             self.draw_text(
                 ctx,
+                font,
                 &zone,
                 &orientation_quat,
                 public_data.projection_zoom,
@@ -587,17 +990,1144 @@ impl Component<SpatialSituationIndicatorData, ()> for SpatialSituationIndicator
             );
         }
 
+        if public_data.sky_ground {
+            self.draw_roll_scale(ctx, &zone, &orientation_quat, public_data.slip);
+        }
+
+        if public_data.fd_visible {
+            self.draw_flight_director(ctx, &zone, &orientation_quat, public_data);
+        }
+
+        if public_data.fpv_visible {
+            self.draw_flight_path_vector(ctx, &zone, &orientation_quat, public_data);
+        }
+
         self.draw_ffd(ctx, &zone);
     }
 }
 
-pub fn components() -> impl Fn(&mut Manager) {
-    |manager: &mut Manager| {
-        let rt = Box::new(RotationalIndicator {});
-        let ssi = Box::new(SpatialSituationIndicator {});
-        let textfield = Box::new(TextField {});
-        manager.register_component_type(rt);
-        manager.register_component_type(textfield);
-        manager.register_component_type(ssi);
+// =========================== TAPE INDICATOR ===========================
+
+// whether v is a multiple of step, give or take the rounding of the tick loop
+fn is_multiple(v: f32, step: f32) -> bool {
+    step > 0.0 && ((v / step).round() * step - v).abs() < step * 0.001
+}
+
+// more ticks or labels than that on one scale aren't legible anyway
+const MAX_TICKS: i64 = 1000;
+
+// indices i of the multiples i * step from `from` to `to`, at most MAX_TICKS of them so
+// that a step tiny against the range can't stall the frame
+fn tick_indices(from: f32, to: f32, step: f32) -> std::ops::RangeInclusive<i64> {
+    let first = (from / step).ceil() as i64;
+    let last = (to / step).floor() as i64;
+
+    first..=last.min(first.saturating_add(MAX_TICKS - 1))
+}
+
+pub struct TapeIndicator {}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Copy, Clone)]
+pub enum TapeOrientation {
+    Vertical,   // values grow upwards, ticks on the right
+    Horizontal, // values grow to the right, ticks at the bottom
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct TapeIndicatorData {
+    pub orientation: TapeOrientation,
+    pub precision: u32,
+    pub unit: String,
+    pub value: f32,
+    pub visible_range: f32, // span of values along the tape, the value is in the middle
+    pub minor_tick: f32,
+    pub major_tick: f32,
+    pub label_every: f32,
+    pub value_min: f32,
+    pub value_ranges: Vec<(f32, Status)>,
+    pub trend: Option<f32>, // predicted change of the value, i.e. in the next 10 seconds
+    pub bugs: Vec<f32>,     // marked values, i.e. selected speed or altitude
+    pub font: Option<resources::FontSelection>,
+}
+
+impl Component<TapeIndicatorData, ()> for TapeIndicator {
+    fn draw(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: DrawZone,
+        __children: &mut [DrawChild],
+        __internal_data: &mut (),
+        data: &TapeIndicatorData,
+    ) {
+        let palette = ctx.resources.palette;
+        let font = ctx.resources.select_font(&data.font);
+        let frame = &ctx.frame;
+
+        // length along the tape and width across it
+        let (length, width) = match data.orientation {
+            TapeOrientation::Vertical => (zone.size.y, zone.size.x),
+            TapeOrientation::Horizontal => (zone.size.x, zone.size.y),
+        };
+
+        if data.visible_range <= 0.0 {
+            return;
+        }
+
+        let half = length / 2.0;
+        let scale = length / data.visible_range;
+        let lowest = data.value - data.visible_range / 2.0;
+        let highest = data.value + data.visible_range / 2.0;
+
+        // along: from the middle towards higher values, across: from the readout side
+        let point = |along: f32, across: f32| match data.orientation {
+            TapeOrientation::Vertical => (zone.left() + across, zone.m.y - along),
+            TapeOrientation::Horizontal => (zone.m.x + along, zone.bottom() + across),
+        };
+        let along = |v: f32| ((v - data.value) * scale).max(-half).min(half);
+        // origin and size of a rectangle between two corners given as (along, across)
+        let rect = |from: (f32, f32), to: (f32, f32)| {
+            let (x0, y0) = point(from.0, from.1);
+            let (x1, y1) = point(to.0, to.1);
+            ((x0.min(x1), y0.min(y1)), ((x1 - x0).abs(), (y1 - y0).abs()))
+        };
+
+        frame.path(
+            |mut path| {
+                let (origin, size) = rect((-half, 0.0), (half, width));
+                path.rect(origin, size);
+                path.fill(palette.status_to_color_bg(Status::Ok), Default::default());
+            },
+            Default::default(),
+        );
+
+        let band_width = width * 0.1;
+        let mut last_range_end = data.value_min;
+
+        for range_end in &data.value_ranges {
+            if range_end.0 > lowest && last_range_end < highest {
+                frame.path(
+                    |mut path| {
+                        let (origin, size) = rect(
+                            (along(last_range_end), width - band_width),
+                            (along(range_end.0), width),
+                        );
+                        path.rect(origin, size);
+                        path.fill(palette.status_to_color(range_end.1), Default::default());
+                    },
+                    Default::default(),
+                );
+            }
+
+            last_range_end = range_end.0;
+        }
+
+        let text_size = width * 0.3;
+        let label_opts = TextOptions {
+            color: palette.caption_color(),
+            size: text_size,
+            align: Alignment::new().center().middle(),
+            ..Default::default()
+        };
+
+        if data.minor_tick > 0.0 {
+            let ticks = tick_indices(lowest, highest, data.minor_tick);
+
+            frame.path(
+                |mut path| {
+                    for i in ticks.clone() {
+                        let v = i as f32 * data.minor_tick;
+                        let tick = match is_multiple(v, data.major_tick) {
+                            true => width * 0.25,
+                            false => width * 0.12,
+                        };
+
+                        path.move_to(point(along(v), width - tick));
+                        path.line_to(point(along(v), width));
+                    }
+
+                    path.stroke(
+                        palette.scale_color(),
+                        StrokeOptions {
+                            width: 1.5,
+                            ..Default::default()
+                        },
+                    );
+                },
+                Default::default(),
+            );
+
+            for i in ticks {
+                let v = i as f32 * data.minor_tick;
+
+                // labels cut by the ends of the tape are left out
+                if is_multiple(v, data.label_every) && along(v).abs() < half - text_size {
+                    frame.text(
+                        font,
+                        point(along(v), width * 0.45),
+                        format_float(v, 0),
+                        label_opts,
+                    );
+                }
+            }
+        }
+
+        // bugs beyond the visible range stay at the ends of the tape
+        for bug in &data.bugs {
+            frame.path(
+                |mut path| {
+                    let a = along(*bug);
+                    let notch = width * 0.06;
+                    let (origin, size) = rect((a - notch, width - 2.5 * notch), (a + notch, width));
+                    path.rect(origin, size);
+                    path.fill(palette.symbology_color(), Default::default());
+                },
+                Default::default(),
+            );
+        }
+
+        match data.trend {
+            Some(trend) => {
+                let to = (trend * scale).max(-half).min(half);
+                let across = width * 0.8;
+                let head = width * 0.06 * to.signum();
+
+                frame.path(
+                    |mut path| {
+                        path.move_to(point(0.0, across));
+                        path.line_to(point(to, across));
+                        path.move_to(point(to - head, across - head.abs()));
+                        path.line_to(point(to, across));
+                        path.line_to(point(to - head, across + head.abs()));
+                        path.stroke(
+                            palette.needle_color(),
+                            StrokeOptions {
+                                width: 2.0,
+                                ..Default::default()
+                            },
+                        );
+                    },
+                    Default::default(),
+                );
+            }
+            None => {}
+        }
+
+        let status = range_status(data.value, data.value_min, &data.value_ranges);
+        let readout = text_size * 0.8;
+
+        frame.path(
+            |mut path| {
+                let (origin, size) = rect((-readout, 0.0), (readout, width * 0.75));
+                path.rect(origin, size);
+                path.fill(palette.background_color(), Default::default());
+                path.stroke(
+                    palette.scale_color(),
+                    StrokeOptions {
+                        width: 2.0,
+                        ..Default::default()
+                    },
+                );
+            },
+            Default::default(),
+        );
+
+        frame.text(
+            font,
+            point(0.0, width * 0.375),
+            format_float(data.value, data.precision) + &data.unit,
+            TextOptions {
+                color: palette.status_to_color_font(status),
+                size: text_size * 1.2,
+                align: Alignment::new().center().middle(),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn init_instance(&self, __ctx: &mut PresentationContext, __data: &TapeIndicatorData) {}
+
+    fn get_default_data(&self) -> Option<TapeIndicatorData> {
+        Some(TapeIndicatorData {
+            orientation: TapeOrientation::Vertical,
+            precision: 0,
+            unit: "".to_string(),
+            value: 0.0,
+            visible_range: 100.0,
+            minor_tick: 5.0,
+            major_tick: 10.0,
+            label_every: 20.0,
+            value_min: 0.0,
+            value_ranges: vec![(1000.0, Status::Ok)],
+            trend: None,
+            bugs: Vec::new(),
+            font: None,
+        })
+    }
+
+    fn resources(&self, data: &TapeIndicatorData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn get_name(&self) -> &'static str {
+        "TapeIndicator"
+    }
+}
+
+// =========================== DIAL INDICATOR ===========================
+
+pub struct DialIndicator {}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Copy, Clone)]
+pub enum NeedleStyle {
+    Pointer, // from the hub towards the scale
+    Bug,     // triangle on the outside of the scale, i.e. a commanded value
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct DialNeedle {
+    pub style: NeedleStyle,
+    pub label: Option<String>, // i.e. the engine number
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct DialIndicatorData {
+    pub start_angle: f32, // radians, clockwise from up
+    pub sweep: f32,       // radians, clockwise from the start
+    pub precision: u32,
+    pub unit: String,
+    pub caption: String,
+    pub values: Vec<f32>,         // one per needle, hooked as a whole
+    pub needles: Vec<DialNeedle>, // style of the needles, pointers without labels by default
+    pub counter: bool,            // digital readout of the first value
+    pub minor_tick: f32,
+    pub major_tick: f32,
+    pub label_every: f32,
+    pub value_min: f32,
+    pub value_ranges: Vec<(f32, Status)>,
+    pub font: Option<resources::FontSelection>,
+}
+
+impl DialIndicatorData {
+    // radians clockwise from up, the needles stop at the ends of the scale
+    fn angle(&self, value: f32) -> f32 {
+        let value_max = match self.value_ranges.last() {
+            Some(range_end) => range_end.0,
+            None => return self.start_angle,
+        };
+
+        let normalized = match value_max > self.value_min {
+            true => ((value - self.value_min) / (value_max - self.value_min)).max(0.0).min(1.0),
+            false => 0.0,
+        };
+
+        self.start_angle + self.sweep * normalized
+    }
+
+    // Whether the value is drawn where value_min is, i.e. the end of a full-circle scale. A
+    // tick or label there is left out if there is one at value_min already.
+    fn wraps_to_start(&self, value: f32, step: f32) -> bool {
+        let turns = (self.angle(value) - self.start_angle) / (2.0 * consts::PI);
+
+        turns.abs() > 0.5
+            && (turns - turns.round()).abs() < 1e-4
+            && is_multiple(self.value_min, step)
+    }
+}
+
+impl Component<DialIndicatorData, ()> for DialIndicator {
+    fn draw(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: DrawZone,
+        __children: &mut [DrawChild],
+        __internal_data: &mut (),
+        data: &DialIndicatorData,
+    ) {
+        let zone = zone.constraint_to_aspect(Some(1.0));
+        let palette = ctx.resources.palette;
+        let font = ctx.resources.select_font(&data.font);
+        let frame = &ctx.frame;
+
+        let radius = zone.size.x / 2.0 * 0.85;
+        let direction = match data.sweep >= 0.0 {
+            true => Direction::Clockwise,
+            false => Direction::CounterClockwise,
+        };
+        // nanovg measures from the x axis
+        let arc_angle = |value: f32| data.angle(value) - consts::PI / 2.0;
+        let on_dial = |angle: f32, r: f32| {
+            let (sin, cos) = (angle - consts::PI / 2.0).sin_cos();
+            (zone.m.x + cos * r, zone.m.y + sin * r)
+        };
+
+        let mut last_range_end = data.value_min;
+
+        for range_end in &data.value_ranges {
+            frame.path(
+                |mut path| {
+                    path.arc(
+                        (zone.m.x, zone.m.y),
+                        radius * 0.95,
+                        arc_angle(last_range_end),
+                        arc_angle(range_end.0),
+                        Winding::Direction(direction),
+                    );
+                    path.stroke(
+                        palette.status_to_color(range_end.1),
+                        StrokeOptions {
+                            width: radius * 0.06,
+                            ..Default::default()
+                        },
+                    );
+                },
+                Default::default(),
+            );
+
+            last_range_end = range_end.0;
+        }
+
+        let value_max = data.value_ranges.last().map(|range_end| range_end.0);
+
+        frame.path(
+            |mut path| {
+                path.arc(
+                    (zone.m.x, zone.m.y),
+                    radius,
+                    data.start_angle - consts::PI / 2.0,
+                    data.start_angle + data.sweep - consts::PI / 2.0,
+                    Winding::Direction(direction),
+                );
+
+                match value_max {
+                    Some(value_max) if data.minor_tick > 0.0 => {
+                        for i in tick_indices(data.value_min, value_max, data.minor_tick) {
+                            let v = i as f32 * data.minor_tick;
+                            if data.wraps_to_start(v, data.minor_tick) {
+                                continue;
+                            }
+
+                            let tick = match is_multiple(v, data.major_tick) {
+                                true => radius * 0.14,
+                                false => radius * 0.07,
+                            };
+
+                            path.move_to(on_dial(data.angle(v), radius));
+                            path.line_to(on_dial(data.angle(v), radius - tick));
+                        }
+                    }
+                    _ => {}
+                }
+
+                path.stroke(
+                    palette.scale_color(),
+                    StrokeOptions {
+                        width: radius * 0.02,
+                        ..Default::default()
+                    },
+                );
+            },
+            Default::default(),
+        );
+
+        let label_opts = TextOptions {
+            color: palette.caption_color(),
+            size: radius * 0.17,
+            align: Alignment::new().center().middle(),
+            ..Default::default()
+        };
+
+        match value_max {
+            Some(value_max) if data.label_every > 0.0 => {
+                for i in tick_indices(data.value_min, value_max, data.label_every) {
+                    let v = i as f32 * data.label_every;
+                    if data.wraps_to_start(v, data.label_every) {
+                        continue;
+                    }
+
+                    frame.text(
+                        font,
+                        on_dial(data.angle(v), radius * 0.7),
+                        format_float(v, 0),
+                        label_opts,
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        frame.text(
+            font,
+            (zone.m.x, zone.m.y - radius * 0.3),
+            &data.caption,
+            label_opts,
+        );
+
+        match (data.counter, data.values.first()) {
+            (true, Some(value)) => {
+                let status = range_status(*value, data.value_min, &data.value_ranges);
+                let (width, height) = (radius * 0.9, radius * 0.28);
+                let top = zone.m.y + radius * 0.3;
+
+                frame.path(
+                    |mut path| {
+                        path.rect((zone.m.x - width / 2.0, top), (width, height));
+                        path.fill(palette.background_color(), Default::default());
+                        path.stroke(
+                            palette.scale_color(),
+                            StrokeOptions {
+                                width: radius * 0.02,
+                                ..Default::default()
+                            },
+                        );
+                    },
+                    Default::default(),
+                );
+
+                frame.text(
+                    font,
+                    (zone.m.x, top + height / 2.0),
+                    format_float(*value, data.precision) + &data.unit,
+                    TextOptions {
+                        color: palette.status_to_color_font(status),
+                        size: height * 0.9,
+                        align: Alignment::new().center().middle(),
+                        ..Default::default()
+                    },
+                );
+            }
+            _ => {}
+        }
+
+        // drawn in reverse, so the first needle ends up on top
+        for (i, value) in data.values.iter().enumerate().rev() {
+            let needle = data.needles.get(i);
+            let style = needle.map_or(NeedleStyle::Pointer, |needle| needle.style);
+            let path_opts = nanovg::PathOptions {
+                transform: Some(
+                    nanovg::Transform::new()
+                        .translate(zone.m.x, zone.m.y)
+                        .rotate(data.angle(*value)),
+                ),
+                ..Default::default()
+            };
+
+            // drawn pointing up, rotated into place
+            frame.path(
+                |mut path| {
+                    match style {
+                        NeedleStyle::Pointer => {
+                            path.move_to((-radius * 0.04, 0.0));
+                            path.line_to((0.0, -radius * 0.9));
+                            path.line_to((radius * 0.04, 0.0));
+                            path.close();
+                            path.fill(palette.needle_color(), Default::default());
+                        }
+                        NeedleStyle::Bug => {
+                            path.move_to((0.0, -radius * 1.02));
+                            path.line_to((-radius * 0.07, -radius * 1.15));
+                            path.line_to((radius * 0.07, -radius * 1.15));
+                            path.close();
+                            path.fill(palette.symbology_color(), Default::default());
+                        }
+                    }
+                },
+                path_opts,
+            );
+
+            match (style, needle.and_then(|needle| needle.label.as_ref())) {
+                (NeedleStyle::Pointer, Some(label)) => {
+                    let center = on_dial(data.angle(*value), radius * 0.55);
+
+                    frame.path(
+                        |mut path| {
+                            path.circle(center, radius * 0.1);
+                            path.fill(palette.needle_color(), Default::default());
+                        },
+                        Default::default(),
+                    );
+
+                    frame.text(
+                        font,
+                        center,
+                        label,
+                        TextOptions {
+                            color: palette.background_color(),
+                            size: radius * 0.15,
+                            align: Alignment::new().center().middle(),
+                            ..Default::default()
+                        },
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        frame.path(
+            |mut path| {
+                path.circle((zone.m.x, zone.m.y), radius * 0.07);
+                path.fill(palette.scale_color(), Default::default());
+            },
+            Default::default(),
+        );
+    }
+
+    fn init_instance(&self, __ctx: &mut PresentationContext, __data: &DialIndicatorData) {}
+
+    fn get_default_data(&self) -> Option<DialIndicatorData> {
+        Some(DialIndicatorData {
+            start_angle: -0.75 * consts::PI,
+            sweep: 1.5 * consts::PI,
+            precision: 1,
+            unit: "".to_string(),
+            caption: "".to_string(),
+            values: vec![0.0],
+            needles: Vec::new(),
+            counter: true,
+            minor_tick: 5.0,
+            major_tick: 10.0,
+            label_every: 20.0,
+            value_min: 0.0,
+            value_ranges: vec![(100.0, Status::Ok)],
+            font: None,
+        })
+    }
+
+    fn resources(&self, data: &DialIndicatorData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn get_name(&self) -> &'static str {
+        "DialIndicator"
+    }
+}
+
+// =========================== HORIZONTAL SITUATION INDICATOR ===========================
+
+pub struct HorizontalSituationIndicator {}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Copy, Clone)]
+pub enum ToFrom {
+    To,
+    From,
+    Off, // no usable navigation signal
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct HorizontalSituationIndicatorData {
+    pub heading: f32, // all angles in radians
+    pub heading_bug: Option<f32>,
+    pub course: Option<f32>, // course pointer with the CDI, hidden without a course
+    pub deviation: f32,      // CDI deflection in dots, positive when the course is to the right
+    pub to_from: ToFrom,
+    pub bearings: Vec<f32>, // bearing pointers, i.e. to NDBs
+    pub font: Option<resources::FontSelection>,
+}
+
+const HSI_DOT_COUNT: i32 = 2;
+
+impl Component<HorizontalSituationIndicatorData, ()> for HorizontalSituationIndicator {
+    fn draw(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: DrawZone,
+        __children: &mut [DrawChild],
+        __internal_data: &mut (),
+        data: &HorizontalSituationIndicatorData,
+    ) {
+        let zone = zone.constraint_to_aspect(Some(1.0));
+        let palette = ctx.resources.palette;
+        let font = ctx.resources.select_font(&data.font);
+        let frame = &ctx.frame;
+
+        let radius = zone.size.x / 2.0 * 0.8;
+        let dot = radius * 0.18;
+
+        // everything that turns with the rose is drawn pointing up and rotated into place
+        let rotated = |direction: f32| nanovg::PathOptions {
+            transform: Some(
+                nanovg::Transform::new()
+                    .translate(zone.m.x, zone.m.y)
+                    .rotate(direction - data.heading),
+            ),
+            ..Default::default()
+        };
+        let on_rose = |direction: f32, r: f32| {
+            let (sin, cos) = (direction - data.heading - consts::PI / 2.0).sin_cos();
+            (zone.m.x + cos * r, zone.m.y + sin * r)
+        };
+        let stroke = |width: f32| StrokeOptions {
+            width: width,
+            ..Default::default()
+        };
+
+        frame.path(
+            |mut path| {
+                path.circle((zone.m.x, zone.m.y), radius);
+                path.fill(palette.status_to_color_bg(Status::Ok), Default::default());
+            },
+            Default::default(),
+        );
+
+        frame.path(
+            |mut path| {
+                for i in 0..72 {
+                    let direction = (i as f32 * 5.0).rad();
+                    let tick = match i % 2 == 0 {
+                        true => radius * 0.12,
+                        false => radius * 0.06,
+                    };
+
+                    path.move_to(on_rose(direction, radius));
+                    path.line_to(on_rose(direction, radius - tick));
+                }
+
+                path.stroke(palette.scale_color(), stroke(radius * 0.015));
+            },
+            Default::default(),
+        );
+
+        let label_opts = TextOptions {
+            color: palette.caption_color(),
+            size: radius * 0.16,
+            align: Alignment::new().center().middle(),
+            ..Default::default()
+        };
+
+        for i in 0..12 {
+            let label = match i {
+                0 => "N".to_string(),
+                3 => "E".to_string(),
+                6 => "S".to_string(),
+                9 => "W".to_string(),
+                _ => (i * 3).to_string(),
+            };
+
+            frame.text(
+                font,
+                on_rose((i as f32 * 30.0).rad(), radius * 0.74),
+                label,
+                label_opts,
+            );
+        }
+
+        for bearing in &data.bearings {
+            frame.path(
+                |mut path| {
+                    path.move_to((0.0, radius * 0.95));
+                    path.line_to((0.0, -radius * 0.95));
+                    path.move_to((-radius * 0.06, -radius * 0.83));
+                    path.line_to((0.0, -radius * 0.95));
+                    path.line_to((radius * 0.06, -radius * 0.83));
+                    path.stroke(palette.symbology_color(), stroke(radius * 0.015));
+                },
+                rotated(*bearing),
+            );
+        }
+
+        match data.course {
+            Some(course) => {
+                let deviation = data.deviation.max(-2.5).min(2.5) * dot;
+
+                frame.path(
+                    |mut path| {
+                        for i in -HSI_DOT_COUNT..=HSI_DOT_COUNT {
+                            if i != 0 {
+                                path.circle((i as f32 * dot, 0.0), radius * 0.025);
+                            }
+                        }
+
+                        path.stroke(palette.scale_color(), stroke(radius * 0.015));
+                    },
+                    rotated(course),
+                );
+
+                frame.path(
+                    |mut path| {
+                        // head
+                        path.move_to((0.0, -radius * 0.45));
+                        path.line_to((0.0, -radius * 0.62));
+                        path.move_to((-radius * 0.07, -radius * 0.5));
+                        path.line_to((0.0, -radius * 0.62));
+                        path.line_to((radius * 0.07, -radius * 0.5));
+                        // tail
+                        path.move_to((0.0, radius * 0.45));
+                        path.line_to((0.0, radius * 0.62));
+                        // deviation bar
+                        path.move_to((deviation, -radius * 0.4));
+                        path.line_to((deviation, radius * 0.4));
+                        path.stroke(palette.needle_color(), stroke(radius * 0.03));
+                    },
+                    rotated(course),
+                );
+
+                let flag = match data.to_from {
+                    ToFrom::To => Some(-1.0),
+                    ToFrom::From => Some(1.0),
+                    ToFrom::Off => None,
+                };
+
+                match flag {
+                    // pointing along the course for TO, away from it for FROM
+                    Some(side) => frame.path(
+                        |mut path| {
+                            let x = -radius * 0.25;
+                            path.move_to((x, side * radius * 0.3));
+                            path.line_to((x - radius * 0.06, side * radius * 0.2));
+                            path.line_to((x + radius * 0.06, side * radius * 0.2));
+                            path.close();
+                            path.fill(palette.needle_color(), Default::default());
+                        },
+                        rotated(course),
+                    ),
+                    None => frame.text(
+                        font,
+                        (zone.m.x - radius * 0.3, zone.m.y - radius * 0.2),
+                        "NAV",
+                        TextOptions {
+                            color: palette.status_to_color_font(Status::Error),
+                            size: radius * 0.14,
+                            align: Alignment::new().center().middle(),
+                            ..Default::default()
+                        },
+                    ),
+                }
+            }
+            None => {}
+        }
+
+        match data.heading_bug {
+            Some(bug) => frame.path(
+                |mut path| {
+                    let (w, h) = (radius * 0.09, radius * 0.07);
+                    path.move_to((-w, -radius));
+                    path.line_to((-w, -radius - h));
+                    path.line_to((w, -radius - h));
+                    path.line_to((w, -radius));
+                    path.line_to((w / 2.0, -radius));
+                    path.line_to((0.0, -radius - h / 2.0));
+                    path.line_to((-w / 2.0, -radius));
+                    path.close();
+                    path.fill(palette.symbology_color(), Default::default());
+                },
+                rotated(bug),
+            ),
+            None => {}
+        }
+
+        // fixed aircraft symbol and lubber line
+        frame.path(
+            |mut path| {
+                let (x, y) = (zone.m.x, zone.m.y);
+                path.move_to((x, y - radius * 0.15));
+                path.line_to((x, y + radius * 0.15));
+                path.move_to((x - radius * 0.12, y - radius * 0.03));
+                path.line_to((x + radius * 0.12, y - radius * 0.03));
+                path.move_to((x - radius * 0.05, y + radius * 0.12));
+                path.line_to((x + radius * 0.05, y + radius * 0.12));
+                path.stroke(palette.symbology_color(), stroke(radius * 0.03));
+            },
+            Default::default(),
+        );
+
+        frame.path(
+            |mut path| {
+                path.move_to((zone.m.x, zone.m.y - radius * 1.02));
+                path.line_to((zone.m.x - radius * 0.06, zone.m.y - radius * 1.14));
+                path.line_to((zone.m.x + radius * 0.06, zone.m.y - radius * 1.14));
+                path.close();
+                path.fill(palette.needle_color(), Default::default());
+            },
+            Default::default(),
+        );
+
+        let heading = (data.heading.deg().round() as i32).rem_euclid(360);
+
+        frame.text(
+            font,
+            (zone.m.x, zone.m.y - radius * 1.2),
+            format!("{:03}", heading),
+            TextOptions {
+                color: palette.status_to_color_font(Status::Ok),
+                size: radius * 0.16,
+                align: Alignment::new().center().bottom(),
+                ..Default::default()
+            },
+        );
+    }
+
+    fn init_instance(
+        &self,
+        __ctx: &mut PresentationContext,
+        __data: &HorizontalSituationIndicatorData,
+    ) {
+    }
+
+    fn get_default_data(&self) -> Option<HorizontalSituationIndicatorData> {
+        Some(HorizontalSituationIndicatorData {
+            heading: 0.0,
+            heading_bug: None,
+            course: None,
+            deviation: 0.0,
+            to_from: ToFrom::Off,
+            bearings: Vec::new(),
+            font: None,
+        })
+    }
+
+    fn resources(&self, data: &HorizontalSituationIndicatorData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn get_name(&self) -> &'static str {
+        "HorizontalSituationIndicator"
+    }
+}
+
+// =========================== IMAGE ===========================
+
+pub struct Image {}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Copy, Clone)]
+pub enum ImageFit {
+    Contain, // whole image visible, keeping its aspect
+    Cover,   // zone filled, keeping its aspect, the overflow is clipped
+    Stretch,
+}
+
+impl Default for ImageFit {
+    fn default() -> ImageFit {
+        ImageFit::Contain
+    }
+}
+
+// a status is drawn in the palette's status color, so it follows palette switches
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum ImageTint {
+    Status(Status),
+    Color(SerializableColor),
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct ImageData {
+    pub image: String, // name in the resource registry
+    #[serde(default)]
+    pub fit: ImageFit,
+    #[serde(default)]
+    pub tint: Option<ImageTint>, // multiplies the image, i.e. white icons in a status color
+    #[serde(default)]
+    pub rotation: f32, // radians, clockwise around the middle of the zone
+}
+
+impl Component<ImageData, ()> for Image {
+    fn draw(
+        &self,
+        ctx: &mut PresentationContext,
+        zone: DrawZone,
+        __children: &mut [DrawChild],
+        __internal_data: &mut (),
+        data: &ImageData,
+    ) {
+        // checked when the layout was loaded, nothing to draw otherwise
+        let (width, height) = match ctx.resources.loaded.image_size(&data.image) {
+            Ok(size) => size,
+            Err(_) => return,
+        };
+
+        let image_aspect = width as f32 / height as f32;
+        let size = match data.fit {
+            ImageFit::Contain => zone.constraint_to_aspect(Some(image_aspect)).size,
+            ImageFit::Cover => match image_aspect > zone.aspect() {
+                true => Vector2::new(image_aspect * zone.size.y, zone.size.y),
+                false => Vector2::new(zone.size.x, zone.size.x / image_aspect),
+            },
+            ImageFit::Stretch => zone.size,
+        };
+
+        let clip = match data.fit {
+            ImageFit::Cover => nanovg::Clip::Scissor(nanovg::Scissor {
+                x: zone.left(),
+                y: zone.bottom(),
+                width: zone.size.x,
+                height: zone.size.y,
+                transform: None,
+            }),
+            _ => nanovg::Clip::None,
+        };
+
+        let path_opts = nanovg::PathOptions {
+            clip: clip,
+            transform: Some(
+                nanovg::Transform::new()
+                    .translate(zone.m.x, zone.m.y)
+                    .rotate(data.rotation),
+            ),
+            ..Default::default()
+        };
+
+        // Dimmed along with the palette through the paint's alpha, so no tinted copy is made
+        // per brightness. The palette's colors come dimmed already, that is undone for the tint.
+        let brightness = ctx.resources.palette.brightness();
+        match brightness > 0.0 {
+            true => {}
+            false => return,
+        }
+
+        let tint = match &data.tint {
+            Some(ImageTint::Status(status)) => {
+                dim_color(ctx.resources.palette.status_to_color(*status), 1.0 / brightness)
+            }
+            Some(ImageTint::Color(tint)) => tint.color,
+            None => Color::from_rgba(255, 255, 255, 255),
+        };
+
+        let frame = &ctx.frame;
+        let __result = ctx.resources.loaded.with_tinted_image(&data.image, tint, |image| {
+            frame.path(
+                |path| {
+                    path.rect((-size.x / 2.0, -size.y / 2.0), (size.x, size.y));
+                    path.fill(
+                        nanovg::ImagePattern {
+                            image: image,
+                            origin: (-size.x / 2.0, -size.y / 2.0),
+                            size: (size.x, size.y),
+                            angle: 0.0,
+                            alpha: brightness,
+                        },
+                        Default::default(),
+                    );
+                },
+                path_opts,
+            );
+        });
+    }
+
+    fn init_instance(&self, __ctx: &mut PresentationContext, __data: &ImageData) {}
+
+    fn get_default_data(&self) -> Option<ImageData> {
+        None
+    }
+
+    fn resources(&self, data: &ImageData) -> Vec<resources::ResourceRef> {
+        vec![resources::ResourceRef::Image(data.image.clone())]
+    }
+
+    fn max_children(&self) -> Option<u32> {
+        Some(0)
+    }
+
+    fn get_name(&self) -> &'static str {
+        "Image"
+    }
+}
+
+pub fn components() -> impl Fn(&mut Manager) {
+    |manager: &mut Manager| {
+        let rt = Box::new(RotationalIndicator {});
+        let ssi = Box::new(SpatialSituationIndicator {});
+        let textfield = Box::new(TextField {});
+        let image = Box::new(Image {});
+        let tape = Box::new(TapeIndicator {});
+        let dial = Box::new(DialIndicator {});
+        let hsi = Box::new(HorizontalSituationIndicator {});
+        manager.register_component_type(rt);
+        manager.register_component_type(textfield);
+        manager.register_component_type(ssi);
+        manager.register_component_type(image);
+        manager.register_component_type(tape);
+        manager.register_component_type(dial);
+        manager.register_component_type(hsi);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGES: [(f32, Status); 3] = [
+        (50.0, Status::Ok),
+        (80.0, Status::Warning),
+        (100.0, Status::Error),
+    ];
+
+    #[test]
+    fn range_ends_belong_to_the_next_range() {
+        assert!(range_status(0.0, 0.0, &RANGES) == Status::Ok);
+        assert!(range_status(49.9, 0.0, &RANGES) == Status::Ok);
+        assert!(range_status(50.0, 0.0, &RANGES) == Status::Warning);
+        assert!(range_status(80.0, 0.0, &RANGES) == Status::Error);
+    }
+
+    #[test]
+    fn values_outside_of_all_ranges_are_errors() {
+        assert!(range_status(-0.1, 0.0, &RANGES) == Status::Error);
+        assert!(range_status(100.0, 0.0, &RANGES) == Status::Error);
+        assert!(range_status(10.0, 0.0, &[]) == Status::Error);
+    }
+
+    #[test]
+    fn tick_indices_cover_the_multiples_in_range() {
+        assert_eq!(tick_indices(0.0, 100.0, 10.0), 0..=10);
+        assert_eq!(tick_indices(-15.0, 15.0, 10.0), -1..=1);
+    }
+
+    #[test]
+    fn tick_indices_are_capped() {
+        assert_eq!(tick_indices(0.0, 1e9, 1.0).count(), MAX_TICKS as usize);
+        assert_eq!(tick_indices(0.0, 1.0, 1e-6).count(), MAX_TICKS as usize);
+    }
+
+    fn dial(sweep: f32) -> DialIndicatorData {
+        let mut data = DialIndicator {}.get_default_data().unwrap();
+        data.start_angle = 0.0;
+        data.sweep = sweep;
+        data.value_min = 0.0;
+        data.value_ranges = vec![(360.0, Status::Ok)];
+        data
+    }
+
+    fn drawn_ticks(data: &DialIndicatorData, step: f32) -> Vec<i64> {
+        tick_indices(data.value_min, 360.0, step)
+            .filter(|i| !data.wraps_to_start(*i as f32 * step, step))
+            .collect()
+    }
+
+    #[test]
+    fn full_circle_dial_draws_the_shared_tick_once() {
+        let ticks = drawn_ticks(&dial(2.0 * consts::PI), 30.0);
+
+        assert_eq!(ticks.len(), 12);
+        assert_eq!(ticks.first(), Some(&0));
+        assert_eq!(ticks.last(), Some(&11));
+    }
+
+    #[test]
+    fn partial_dial_keeps_both_ends() {
+        let ticks = drawn_ticks(&dial(1.5 * consts::PI), 30.0);
+
+        assert_eq!(ticks.len(), 13);
+        assert_eq!(ticks.last(), Some(&12));
+    }
+
+    #[test]
+    fn full_circle_dial_keeps_the_end_without_a_tick_at_the_start() {
+        let mut data = dial(2.0 * consts::PI);
+        data.value_min = 5.0;
+        data.value_ranges = vec![(365.0, Status::Ok)];
+
+        // no tick is drawn at the start, so the end is kept
+        assert!(!data.wraps_to_start(365.0, 30.0));
     }
 }
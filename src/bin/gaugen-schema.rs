@@ -0,0 +1,21 @@
+extern crate gaugen;
+extern crate serde_json;
+
+use std::env;
+use std::fs;
+
+// Prints the JSON Schema of layout files built from the bundled components,
+// or saves it if a path is given: gaugen-schema [output.json]
+fn main() {
+    let mut manager = gaugen::Manager::new();
+    gaugen::basic_components::components()(&mut manager);
+    gaugen::geometry_components::components()(&mut manager);
+
+    let schema = serde_json::to_string_pretty(&manager.export_schema())
+        .expect("Failed to serialize the schema");
+
+    match env::args().nth(1) {
+        Some(path) => fs::write(&path, schema).expect("Failed to write the schema"),
+        None => println!("{}", schema),
+    }
+}
@@ -1,8 +1,13 @@
+use crate::resources::{ContextResources, FontSelection, ResourceError};
 use nanovg::{Color, Font};
+use std::collections::HashMap;
 use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 
-#[derive(serde::Serialize, serde::Deserialize, PartialEq, Copy, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, PartialEq, Copy, Clone)]
 pub enum Status {
     Ok,
     Warning,
@@ -82,11 +87,102 @@ impl serde::Serialize for SerializableColor {
     }
 }
 
+impl schemars::JsonSchema for SerializableColor {
+    fn schema_name() -> String {
+        "Color".to_string()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            instance_type: Some(schemars::schema::InstanceType::String.into()),
+            string: Some(Box::new(schemars::schema::StringValidation {
+                pattern: Some("^[0-9a-fA-F]{8}$".to_string()),
+                ..Default::default()
+            })),
+            metadata: Some(Box::new(schemars::schema::Metadata {
+                description: Some("Color in AARRGGBB format".to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 pub trait Palette {
     fn status_to_color(&self, s: Status) -> Color;
     fn status_to_color_font(&self, s: Status) -> Color;
     fn status_to_color_bg(&self, s: Status) -> Color;
     fn soft_front_color(&self) -> Color;
+
+    fn background_color(&self) -> Color {
+        Color::from_rgba(0, 0, 0, 255)
+    }
+
+    // Semantic roles of the parts of the built-in components, derived from the colors above
+    // unless a palette defines them.
+
+    // value arcs, needles and pointers
+    fn needle_color(&self) -> Color {
+        self.status_to_color_font(Status::Ok)
+    }
+
+    // scale outlines and ticks
+    fn scale_color(&self) -> Color {
+        self.soft_front_color()
+    }
+
+    fn caption_color(&self) -> Color {
+        self.soft_front_color()
+    }
+
+    // pitch and heading ladders of attitude displays
+    fn ladder_color(&self) -> Color {
+        self.soft_front_color()
+    }
+
+    fn ladder_label_color(&self) -> Color {
+        self.status_to_color_font(Status::Ok)
+    }
+
+    // aircraft symbol and flight director
+    fn symbology_color(&self) -> Color {
+        self.status_to_color_font(Status::Warning)
+    }
+
+    fn sky_color(&self) -> Color {
+        Color::from_rgba(0x10, 0x60, 0xb0, 0xff)
+    }
+
+    fn ground_color(&self) -> Color {
+        Color::from_rgba(0x70, 0x48, 0x18, 0xff)
+    }
+
+    fn horizon_color(&self) -> Color {
+        self.status_to_color_font(Status::Ok)
+    }
+
+    // colors custom components look up by name
+    fn named_color(&self, __name: &str) -> Option<Color> {
+        None
+    }
+
+    // Whether the colors change on their own (i.e. during a cross-fade), the screen is
+    // redrawn while it's true even if nothing else changed.
+    fn is_animated(&self) -> bool {
+        false
+    }
+
+    // changes whenever the colors are changed, so a screen drawn with it knows to redraw
+    fn revision(&self) -> u64 {
+        0
+    }
+
+    // Factor the palette's colors are dimmed by, applied through Resources::dim to colors
+    // that don't come from the palette (component data, images, overlays).
+    fn brightness(&self) -> f32 {
+        1.0
+    }
 }
 
 pub struct DarkPalette {}
@@ -119,11 +215,396 @@ impl Palette for DarkPalette {
     fn soft_front_color(&self) -> Color {
         Color::from_rgba(128, 128, 128, 255)
     }
+
+    fn background_color(&self) -> Color {
+        Color::from_rgba(0, 0, 0, 255)
+    }
+
+    fn needle_color(&self) -> Color {
+        Color::from_rgba(255, 255, 255, 255)
+    }
+
+    fn scale_color(&self) -> Color {
+        Color::from_rgba(160, 160, 160, 255)
+    }
+
+    fn caption_color(&self) -> Color {
+        Color::from_rgba(180, 180, 180, 255)
+    }
+
+    fn ladder_color(&self) -> Color {
+        Color::from_rgba(0x50, 0x50, 0x50, 0xff)
+    }
+
+    fn ladder_label_color(&self) -> Color {
+        Color::from_rgba(255, 255, 255, 255)
+    }
+
+    fn symbology_color(&self) -> Color {
+        Color::from_rgba(0xff, 0xff, 0x20, 0xa2)
+    }
+
+    fn sky_color(&self) -> Color {
+        Color::from_rgba(0x10, 0x60, 0xb0, 0xff)
+    }
+
+    fn ground_color(&self) -> Color {
+        Color::from_rgba(0x70, 0x48, 0x18, 0xff)
+    }
+
+    fn horizon_color(&self) -> Color {
+        Color::from_rgba(255, 255, 255, 255)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct StatusColors {
+    pub ok: SerializableColor,
+    pub warning: SerializableColor,
+    pub error: SerializableColor,
+}
+
+impl StatusColors {
+    pub fn get(&self, s: Status) -> Color {
+        match s {
+            Status::Ok => self.ok.color,
+            Status::Warning => self.warning.color,
+            Status::Error => self.error.color,
+        }
+    }
+}
+
+// Palette defined in a JSON file, see resources/palettes. Roles left out are derived like
+// the defaults of Palette.
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+pub struct JsonPalette {
+    pub status: StatusColors,      // ranges, bars
+    pub status_font: StatusColors, // values and captions
+    pub status_bg: StatusColors,
+    pub soft_front: SerializableColor,
+    pub background: SerializableColor,
+    #[serde(default)]
+    pub needle: Option<SerializableColor>,
+    #[serde(default)]
+    pub scale: Option<SerializableColor>,
+    #[serde(default)]
+    pub caption: Option<SerializableColor>,
+    #[serde(default)]
+    pub ladder: Option<SerializableColor>,
+    #[serde(default)]
+    pub ladder_label: Option<SerializableColor>,
+    #[serde(default)]
+    pub symbology: Option<SerializableColor>,
+    #[serde(default)]
+    pub sky: Option<SerializableColor>,
+    #[serde(default)]
+    pub ground: Option<SerializableColor>,
+    #[serde(default)]
+    pub horizon: Option<SerializableColor>,
+    #[serde(default)]
+    pub colors: HashMap<String, SerializableColor>,
+}
+
+impl JsonPalette {
+    pub fn from_file(path: &str) -> Result<JsonPalette, ResourceError> {
+        let json = fs::read_to_string(path).map_err(|er| ResourceError::Io {
+            path: PathBuf::from(path),
+            source: er,
+        })?;
+
+        serde_json::from_str(&json).map_err(|er| ResourceError::Decode {
+            path: PathBuf::from(path),
+            message: er.to_string(),
+        })
+    }
+}
+
+impl Palette for JsonPalette {
+    fn status_to_color(&self, s: Status) -> Color {
+        self.status.get(s)
+    }
+
+    fn status_to_color_font(&self, s: Status) -> Color {
+        self.status_font.get(s)
+    }
+
+    fn status_to_color_bg(&self, s: Status) -> Color {
+        self.status_bg.get(s)
+    }
+
+    fn soft_front_color(&self) -> Color {
+        self.soft_front.color
+    }
+
+    fn background_color(&self) -> Color {
+        self.background.color
+    }
+
+    fn needle_color(&self) -> Color {
+        match &self.needle {
+            Some(color) => color.color,
+            None => self.status_to_color_font(Status::Ok),
+        }
+    }
+
+    fn scale_color(&self) -> Color {
+        match &self.scale {
+            Some(color) => color.color,
+            None => self.soft_front_color(),
+        }
+    }
+
+    fn caption_color(&self) -> Color {
+        match &self.caption {
+            Some(color) => color.color,
+            None => self.soft_front_color(),
+        }
+    }
+
+    fn ladder_color(&self) -> Color {
+        match &self.ladder {
+            Some(color) => color.color,
+            None => self.soft_front_color(),
+        }
+    }
+
+    fn ladder_label_color(&self) -> Color {
+        match &self.ladder_label {
+            Some(color) => color.color,
+            None => self.status_to_color_font(Status::Ok),
+        }
+    }
+
+    fn symbology_color(&self) -> Color {
+        match &self.symbology {
+            Some(color) => color.color,
+            None => self.status_to_color_font(Status::Warning),
+        }
+    }
+
+    fn sky_color(&self) -> Color {
+        match &self.sky {
+            Some(color) => color.color,
+            None => Color::from_rgba(0x10, 0x60, 0xb0, 0xff),
+        }
+    }
+
+    fn ground_color(&self) -> Color {
+        match &self.ground {
+            Some(color) => color.color,
+            None => Color::from_rgba(0x70, 0x48, 0x18, 0xff),
+        }
+    }
+
+    fn horizon_color(&self) -> Color {
+        match &self.horizon {
+            Some(color) => color.color,
+            None => self.status_to_color_font(Status::Ok),
+        }
+    }
+
+    fn named_color(&self, name: &str) -> Option<Color> {
+        self.colors.get(name).map(|color| color.color)
+    }
+}
+
+// rgb multiplied by the brightness, alpha is kept
+pub fn dim_color(color: Color, brightness: f32) -> Color {
+    Color::new(
+        color.red() * brightness,
+        color.green() * brightness,
+        color.blue() * brightness,
+        color.alpha(),
+    )
+}
+
+fn mix_colors(from: Color, to: Color, t: f32) -> Color {
+    let mix = |a: f32, b: f32| a + (b - a) * t;
+
+    Color::new(
+        mix(from.red(), to.red()),
+        mix(from.green(), to.green()),
+        mix(from.blue(), to.blue()),
+        mix(from.alpha(), to.alpha()),
+    )
+}
+
+// Palette that can be swapped between frames, optionally cross-fading from the previous
+// one, and dimmed as a whole (i.e. cockpit display brightness), as in
+// switch.switch_to(Box::new(JsonPalette::from_file("night.json")?), Some(fade))
+pub struct PaletteSwitch {
+    current: Box<dyn Palette>,
+    previous: Option<Box<dyn Palette>>,
+    fade_start: Instant,
+    fade: Duration,
+    brightness: f32,
+    revision: u64,
+}
+
+impl PaletteSwitch {
+    pub fn new(palette: Box<dyn Palette>) -> PaletteSwitch {
+        PaletteSwitch {
+            current: palette,
+            previous: None,
+            fade_start: Instant::now(),
+            fade: Duration::from_secs(0),
+            brightness: 1.0,
+            revision: 0,
+        }
+    }
+
+    // a fade still in progress is cut short, the new one starts from the last palette
+    pub fn switch_to(&mut self, palette: Box<dyn Palette>, fade: Option<Duration>) {
+        let previous = std::mem::replace(&mut self.current, palette);
+
+        self.previous = match fade {
+            Some(_) => Some(previous),
+            None => None,
+        };
+        self.fade_start = Instant::now();
+        self.fade = fade.unwrap_or(Duration::from_secs(0));
+        self.revision += 1;
+    }
+
+    pub fn current(&self) -> &dyn Palette {
+        &*self.current
+    }
+
+    // 0.0 (black) to 1.0 (the palette's colors), alpha is kept
+    pub fn set_brightness(&mut self, brightness: f32) {
+        self.brightness = brightness.max(0.0).min(1.0);
+        self.revision += 1;
+    }
+
+    pub fn brightness(&self) -> f32 {
+        self.brightness
+    }
+
+    // 0.0 at the start of the fade, 1.0 when done
+    fn fade_progress(&self) -> f32 {
+        let elapsed = self.fade_start.elapsed();
+
+        match self.previous {
+            Some(_) if elapsed < self.fade => {
+                (elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9)
+                    / (self.fade.as_secs() as f32 + self.fade.subsec_nanos() as f32 * 1e-9)
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn output<F>(&self, color: F) -> Color
+    where
+        F: Fn(&dyn Palette) -> Color,
+    {
+        let target = color(&*self.current);
+        let progress = self.fade_progress();
+
+        let mixed = match &self.previous {
+            Some(previous) if progress < 1.0 => mix_colors(color(&**previous), target, progress),
+            _ => target,
+        };
+
+        dim_color(mixed, self.brightness)
+    }
+}
+
+impl Palette for PaletteSwitch {
+    fn status_to_color(&self, s: Status) -> Color {
+        self.output(|palette| palette.status_to_color(s))
+    }
+
+    fn status_to_color_font(&self, s: Status) -> Color {
+        self.output(|palette| palette.status_to_color_font(s))
+    }
+
+    fn status_to_color_bg(&self, s: Status) -> Color {
+        self.output(|palette| palette.status_to_color_bg(s))
+    }
+
+    fn soft_front_color(&self) -> Color {
+        self.output(|palette| palette.soft_front_color())
+    }
+
+    fn background_color(&self) -> Color {
+        self.output(|palette| palette.background_color())
+    }
+
+    fn needle_color(&self) -> Color {
+        self.output(|palette| palette.needle_color())
+    }
+
+    fn scale_color(&self) -> Color {
+        self.output(|palette| palette.scale_color())
+    }
+
+    fn caption_color(&self) -> Color {
+        self.output(|palette| palette.caption_color())
+    }
+
+    fn ladder_color(&self) -> Color {
+        self.output(|palette| palette.ladder_color())
+    }
+
+    fn ladder_label_color(&self) -> Color {
+        self.output(|palette| palette.ladder_label_color())
+    }
+
+    fn symbology_color(&self) -> Color {
+        self.output(|palette| palette.symbology_color())
+    }
+
+    fn sky_color(&self) -> Color {
+        self.output(|palette| palette.sky_color())
+    }
+
+    fn ground_color(&self) -> Color {
+        self.output(|palette| palette.ground_color())
+    }
+
+    fn horizon_color(&self) -> Color {
+        self.output(|palette| palette.horizon_color())
+    }
+
+    // falls back to the current palette's color if the previous one doesn't have it
+    fn named_color(&self, name: &str) -> Option<Color> {
+        let target = self.current.named_color(name)?;
+
+        Some(self.output(|palette| palette.named_color(name).unwrap_or(target)))
+    }
+
+    fn is_animated(&self) -> bool {
+        self.fade_progress() < 1.0 || self.current.is_animated()
+    }
+
+    fn revision(&self) -> u64 {
+        self.revision + self.current.revision()
+    }
+
+    fn brightness(&self) -> f32 {
+        self.brightness
+    }
 }
 
 pub struct Resources<'a> {
     pub palette: &'a dyn Palette,
-    pub font: Font<'a>,
+    pub font: Font<'a>, // the default font
+    pub loaded: &'a ContextResources, // named fonts and images
+}
+
+impl<'a> Resources<'a> {
+    // the default font if there is no selection or the family isn't loaded
+    pub fn select_font(&self, selection: &Option<FontSelection>) -> Font<'a> {
+        match selection {
+            Some(selection) => self.loaded.select_font(selection).unwrap_or(self.font),
+            None => self.font,
+        }
+    }
+
+    // dims a color that doesn't come from the palette like the palette's own colors
+    pub fn dim(&self, color: Color) -> Color {
+        dim_color(color, self.palette.brightness())
+    }
 }
 
 pub struct PresentationContext<'a> {
@@ -6,9 +6,9 @@ use nalgebra::Vector2;
 
 // =========================== SPACER ===========================
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub struct SpacerInstance {
-    spacing: f32,
+    pub spacing: f32,
 }
 
 pub struct Spacer {}
@@ -49,23 +49,23 @@ impl Component<SpacerInstance, ()> for Spacer {
 
 // =========================== SPLIT ===========================
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, std::cmp::PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, std::cmp::PartialEq)]
 pub enum SplitDirection {
     Horizontal,
     Vertical,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone, std::cmp::PartialEq)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, std::cmp::PartialEq)]
 pub enum SplitMode {
     EqualArea,
     EqualSide,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub struct SplitInstance {
-    spacing: f32,
-    direction: SplitDirection,
-    mode: SplitMode,
+    pub spacing: f32,
+    pub direction: SplitDirection,
+    pub mode: SplitMode,
 }
 
 pub struct Split {
@@ -240,17 +240,18 @@ impl Component<SplitInstance, SplitInternalData> for Split {
 
 // ===========================
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub enum GroupingBoxTitleSize {
     RelativeToHeight(f32),
     Absolute(f32),
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
 pub struct GroupingBoxData {
     pub spacing: f32,
     pub title_size: GroupingBoxTitleSize,
     pub title: String,
+    pub font: Option<resources::FontSelection>,
 }
 
 pub struct GroupingBox {}
@@ -270,9 +271,14 @@ impl Component<GroupingBoxData, ()> for GroupingBox {
             spacing: 0.9,
             title_size: GroupingBoxTitleSize::RelativeToHeight(0.2),
             title: "GroupingBox".to_string(),
+            font: None,
         })
     }
 
+    fn resources(&self, data: &GroupingBoxData) -> Vec<resources::ResourceRef> {
+        resources::font_refs(&[&data.font])
+    }
+
     fn init_instance(&self, __ctx: &mut frontend::PresentationContext, __data: &GroupingBoxData) {}
 
     fn draw(
@@ -319,15 +325,17 @@ impl Component<GroupingBoxData, ()> for GroupingBox {
             ..Default::default()
         };
 
+        let font = ctx.resources.select_font(&public_data.font);
+
         ctx.frame.text_box(
-            ctx.resources.font,
+            font,
             (text_zone.left(), text_zone.m.y),
             public_data.title.as_str(),
             text_opts,
         );
 
         let bounds = ctx.frame.text_box_bounds(
-            ctx.resources.font,
+            font,
             (0.0, 0.0),
             public_data.title.as_str(),
             text_opts,
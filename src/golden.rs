@@ -0,0 +1,341 @@
+use crate::frontend;
+use crate::headless::{HeadlessSession, Snapshot};
+use crate::*;
+
+extern crate image;
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Golden-image regression testing: a layout is rendered for a sequence of frames, each at a
+// fixed time and with its own hooks, and every frame is compared against a reference PNG
+// <reference_dir>/<name>_<frame>.png. On mismatch, <name>_<frame>.actual.png and
+// <name>_<frame>.diff.png (mismatching pixels in red) are written next to the reference.
+
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct GoldenFrame {
+    pub time: f32,
+    #[serde(default)]
+    pub hooks: Hooks,
+}
+
+pub struct FrameMismatch {
+    pub frame: usize,
+    pub mismatched_pixels: usize,
+    pub diff: PathBuf,
+}
+
+pub enum GoldenError {
+    Layout(LayoutError),
+    Frames {
+        path: String,
+        message: String,
+    },
+    MissingReference {
+        frame: usize,
+        path: PathBuf,
+    },
+    Image {
+        path: PathBuf,
+        message: String,
+    },
+    SizeMismatch {
+        frame: usize,
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+    Mismatch(Vec<FrameMismatch>),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GoldenError::Layout(er) => write!(f, "{}", er),
+            GoldenError::Frames { path, message } => {
+                write!(f, "failed to load frames '{}': {}", path, message)
+            }
+            GoldenError::MissingReference { frame, path } => write!(
+                f,
+                "frame {}: reference image '{}' does not exist",
+                frame,
+                path.display()
+            ),
+            GoldenError::Image { path, message } => {
+                write!(f, "image '{}': {}", path.display(), message)
+            }
+            GoldenError::SizeMismatch {
+                frame,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "frame {}: reference is {}x{}, rendered frame is {}x{}",
+                frame, expected.0, expected.1, actual.0, actual.1
+            ),
+            GoldenError::Mismatch(mismatches) => {
+                for mismatch in mismatches {
+                    writeln!(
+                        f,
+                        "frame {}: {} pixels differ, see '{}'",
+                        mismatch.frame,
+                        mismatch.mismatched_pixels,
+                        mismatch.diff.display()
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Debug for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl From<LayoutError> for GoldenError {
+    fn from(er: LayoutError) -> GoldenError {
+        GoldenError::Layout(er)
+    }
+}
+
+pub struct GoldenTest {
+    name: String,
+    layout: String,
+    reference_dir: PathBuf,
+    frames: Vec<GoldenFrame>,
+    tolerance: u8,
+    update_references: bool,
+}
+
+impl GoldenTest {
+    pub fn new(name: &str, layout: &str, reference_dir: &str) -> GoldenTest {
+        GoldenTest {
+            name: name.to_string(),
+            layout: layout.to_string(),
+            reference_dir: PathBuf::from(reference_dir),
+            frames: Vec::new(),
+            tolerance: 2,
+            update_references: false,
+        }
+    }
+
+    pub fn frame(mut self, time: f32, hooks: Hooks) -> Self {
+        self.frames.push(GoldenFrame {
+            time: time,
+            hooks: hooks,
+        });
+        self
+    }
+
+    // JSON array of {"time": 0.5, "hooks": {"component": {"property": value}}}
+    pub fn frames_from_file(mut self, path: &str) -> Result<Self, GoldenError> {
+        let frames_error = |message: String| GoldenError::Frames {
+            path: path.to_string(),
+            message: message,
+        };
+
+        let json = fs::read_to_string(path).map_err(|er| frames_error(er.to_string()))?;
+        let frames: Vec<GoldenFrame> =
+            serde_json::from_str(&json).map_err(|er| frames_error(er.to_string()))?;
+
+        self.frames.extend(frames);
+        Ok(self)
+    }
+
+    // maximal allowed per-channel difference of a pixel
+    pub fn tolerance(mut self, tolerance: u8) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    // overwrite references with rendered frames instead of comparing
+    pub fn update_references(mut self, update_references: bool) -> Self {
+        self.update_references = update_references;
+        self
+    }
+
+    fn frame_path(&self, frame: usize, suffix: &str) -> PathBuf {
+        self.reference_dir
+            .join(format!("{}_{:03}{}.png", self.name, frame, suffix))
+    }
+
+    pub fn run(
+        &self,
+        session: &mut HeadlessSession,
+        palette: &dyn frontend::Palette,
+    ) -> Result<(), GoldenError> {
+        let mut view = session.new_view(&self.layout)?;
+        let mut mismatches = Vec::new();
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            session.draw_at(&mut view, palette, &frame.hooks, frame.time);
+            let actual = session.snapshot();
+            let reference_path = self.frame_path(i, "");
+
+            if self.update_references {
+                save(&actual, &reference_path)?;
+                continue;
+            }
+
+            if !reference_path.exists() {
+                return Err(GoldenError::MissingReference {
+                    frame: i,
+                    path: reference_path,
+                });
+            }
+
+            let reference = Snapshot::from_png(&reference_path).map_err(|er| GoldenError::Image {
+                path: reference_path.clone(),
+                message: er.to_string(),
+            })?;
+
+            if (reference.width, reference.height) != (actual.width, actual.height) {
+                return Err(GoldenError::SizeMismatch {
+                    frame: i,
+                    expected: (reference.width, reference.height),
+                    actual: (actual.width, actual.height),
+                });
+            }
+
+            let (diff, mismatched_pixels) = compare(&reference, &actual, self.tolerance);
+
+            if mismatched_pixels > 0 {
+                let diff_path = self.frame_path(i, ".diff");
+                save(&actual, &self.frame_path(i, ".actual"))?;
+                save(&diff, &diff_path)?;
+
+                mismatches.push(FrameMismatch {
+                    frame: i,
+                    mismatched_pixels: mismatched_pixels,
+                    diff: diff_path,
+                });
+            }
+        }
+
+        match mismatches.len() {
+            0 => Ok(()),
+            _ => Err(GoldenError::Mismatch(mismatches)),
+        }
+    }
+}
+
+fn save(snapshot: &Snapshot, path: &Path) -> Result<(), GoldenError> {
+    snapshot.save_png(path).map_err(|er| GoldenError::Image {
+        path: path.to_path_buf(),
+        message: er.to_string(),
+    })
+}
+
+// returns the diff image (dimmed reference, differing pixels in red) and the number of
+// pixels with any channel differing by more than the tolerance. Snapshots of different
+// sizes are compared over the larger of both, pixels missing in either one differ.
+pub fn compare(reference: &Snapshot, actual: &Snapshot, tolerance: u8) -> (Snapshot, usize) {
+    let width = reference.width.max(actual.width);
+    let height = reference.height.max(actual.height);
+    let mut diff = Snapshot::new(width, height);
+    let mut mismatched_pixels = 0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let inside = |snapshot: &Snapshot| x < snapshot.width && y < snapshot.height;
+
+            if !inside(reference) || !inside(actual) {
+                mismatched_pixels += 1;
+                diff.set_pixel(x, y, [255, 0, 0, 255]);
+                continue;
+            }
+
+            let r = reference.pixel(x, y);
+            let a = actual.pixel(x, y);
+
+            let differs = r
+                .iter()
+                .zip(a.iter())
+                .any(|(r, a)| (*r as i32 - *a as i32).abs() > tolerance as i32);
+
+            if differs {
+                mismatched_pixels += 1;
+                diff.set_pixel(x, y, [255, 0, 0, 255]);
+            } else {
+                let gray = ((r[0] as u32 + r[1] as u32 + r[2] as u32) / 3 / 4) as u8;
+                diff.set_pixel(x, y, [gray, gray, gray, 255]);
+            }
+        }
+    }
+
+    (diff, mismatched_pixels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filled(width: u32, height: u32, rgba: [u8; 4]) -> Snapshot {
+        let mut snapshot = Snapshot::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                snapshot.set_pixel(x, y, rgba);
+            }
+        }
+
+        snapshot
+    }
+
+    #[test]
+    fn identical_snapshots_match() {
+        let reference = filled(4, 3, [10, 20, 30, 255]);
+        let (diff, mismatched) = compare(&reference, &reference, 0);
+
+        assert_eq!(mismatched, 0);
+        assert_eq!((diff.width, diff.height), (4, 3));
+    }
+
+    #[test]
+    fn differences_within_tolerance_match() {
+        let reference = filled(2, 2, [100, 100, 100, 255]);
+        let actual = filled(2, 2, [102, 98, 100, 255]);
+
+        assert_eq!(compare(&reference, &actual, 2).1, 0);
+        assert_eq!(compare(&reference, &actual, 1).1, 4);
+    }
+
+    #[test]
+    fn any_channel_beyond_tolerance_differs() {
+        let reference = filled(2, 1, [100, 100, 100, 255]);
+        let mut actual = filled(2, 1, [100, 100, 100, 255]);
+        actual.set_pixel(1, 0, [100, 100, 100, 200]);
+
+        assert_eq!(compare(&reference, &actual, 2).1, 1);
+    }
+
+    #[test]
+    fn diff_marks_mismatches_red_and_dims_the_rest() {
+        let reference = filled(2, 1, [120, 120, 120, 255]);
+        let mut actual = filled(2, 1, [120, 120, 120, 255]);
+        actual.set_pixel(0, 0, [0, 0, 0, 255]);
+
+        let (diff, mismatched) = compare(&reference, &actual, 0);
+
+        assert_eq!(mismatched, 1);
+        assert_eq!(diff.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(diff.pixel(1, 0), [30, 30, 30, 255]);
+    }
+
+    #[test]
+    fn pixels_outside_either_snapshot_differ() {
+        let reference = filled(3, 2, [50, 50, 50, 255]);
+        let actual = filled(2, 3, [50, 50, 50, 255]);
+
+        let (diff, mismatched) = compare(&reference, &actual, 0);
+
+        assert_eq!((diff.width, diff.height), (3, 3));
+        // the column x = 2 of the reference and the row y = 2 of the actual snapshot
+        assert_eq!(mismatched, 9 - 4);
+        assert_eq!(diff.pixel(2, 2), [255, 0, 0, 255]);
+        assert_eq!(diff.pixel(1, 1), [12, 12, 12, 255]);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::frontend;
+use crate::resources::{ContextResources, ResourceError};
+use crate::session::{self, SessionBuilder};
+use crate::*;
+
+extern crate gl;
+extern crate glutin;
+extern crate image;
+extern crate nanovg;
+
+use glutin::GlContext;
+use std::path::Path;
+use std::time::Instant;
+
+// RGBA, 8 bits per channel, rows stored top to bottom
+#[derive(Clone)]
+pub struct Snapshot {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Snapshot {
+    pub fn new(width: u32, height: u32) -> Snapshot {
+        Snapshot {
+            width: width,
+            height: height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    pub fn from_png<P: AsRef<Path>>(path: P) -> image::ImageResult<Snapshot> {
+        let rgba = image::open(path)?.to_rgba();
+        let (width, height) = rgba.dimensions();
+
+        Ok(Snapshot {
+            width: width,
+            height: height,
+            pixels: rgba.into_raw(),
+        })
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        image::save_buffer(
+            path,
+            &self.pixels,
+            self.width,
+            self.height,
+            image::ColorType::RGBA(8),
+        )
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * self.width + x) * 4) as usize;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [u8; 4]) {
+        let i = ((y * self.width + x) * 4) as usize;
+        self.pixels[i..i + 4].copy_from_slice(&rgba);
+    }
+}
+
+struct Framebuffer {
+    fbo: gl::types::GLuint,
+    color: gl::types::GLuint,
+    depth_stencil: gl::types::GLuint,
+    width: u32,
+    height: u32,
+}
+
+impl Framebuffer {
+    // requires a current GL context, leaves the new framebuffer bound
+    fn new(width: u32, height: u32) -> Framebuffer {
+        let mut fb = Framebuffer {
+            fbo: 0,
+            color: 0,
+            depth_stencil: 0,
+            width: width,
+            height: height,
+        };
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut fb.fbo);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, fb.fbo);
+
+            gl::GenRenderbuffers(1, &mut fb.color);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, fb.color);
+            gl::RenderbufferStorage(gl::RENDERBUFFER, gl::RGBA8, width as i32, height as i32);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                fb.color,
+            );
+
+            // nanovg relies on the stencil buffer for fills and stencil strokes
+            gl::GenRenderbuffers(1, &mut fb.depth_stencil);
+            gl::BindRenderbuffer(gl::RENDERBUFFER, fb.depth_stencil);
+            gl::RenderbufferStorage(
+                gl::RENDERBUFFER,
+                gl::DEPTH24_STENCIL8,
+                width as i32,
+                height as i32,
+            );
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_STENCIL_ATTACHMENT,
+                gl::RENDERBUFFER,
+                fb.depth_stencil,
+            );
+
+            gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+            assert!(
+                gl::CheckFramebufferStatus(gl::FRAMEBUFFER) == gl::FRAMEBUFFER_COMPLETE,
+                "Offscreen framebuffer is incomplete"
+            );
+        }
+
+        fb
+    }
+
+    fn bind(&self) {
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.fbo);
+        }
+    }
+
+    fn read(&self) -> Snapshot {
+        let mut snapshot = Snapshot::new(self.width, self.height);
+        let stride = (self.width * 4) as usize;
+        let mut flipped = vec![0u8; snapshot.pixels.len()];
+
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.fbo);
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::Finish();
+            gl::ReadPixels(
+                0,
+                0,
+                self.width as i32,
+                self.height as i32,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                flipped.as_mut_ptr() as *mut std::ffi::c_void,
+            );
+        }
+
+        // GL returns rows bottom to top
+        for y in 0..self.height as usize {
+            let src = (self.height as usize - 1 - y) * stride;
+            snapshot.pixels[y * stride..(y + 1) * stride]
+                .copy_from_slice(&flipped[src..src + stride]);
+        }
+
+        snapshot
+    }
+}
+
+impl Drop for Framebuffer {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteRenderbuffers(1, &self.depth_stencil);
+            gl::DeleteRenderbuffers(1, &self.color);
+            gl::DeleteFramebuffers(1, &self.fbo);
+        }
+    }
+}
+
+pub struct HeadlessSession<'a> {
+    resources: &'a ContextResources,
+    manager: Manager,
+    framebuffer: Framebuffer,
+    start_time: Instant,
+}
+
+impl SessionBuilder {
+    // Renders through glutin's headless context (OSMesa on Linux, so Mesa's
+    // llvmpipe/softpipe can be used on machines without display or GPU). Fails with
+    // ResourceError::Context if no GL 3.2 core context can be created, or if the resources
+    // can't be loaded into it.
+    pub fn init_headless<F: Fn(&mut HeadlessSession)>(
+        self,
+        width: u32,
+        height: u32,
+        handler: F,
+    ) -> Result<(), ResourceError> {
+        let gl_context = glutin::HeadlessRendererBuilder::new(width, height)
+            .with_gl(glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 2)))
+            .with_gl_profile(glutin::GlProfile::Core)
+            .build()
+            .map_err(|er| ResourceError::Context(er.to_string()))?;
+
+        unsafe {
+            gl_context
+                .make_current()
+                .map_err(|er| ResourceError::Context(format!("{:?}", er)))?;
+            gl::load_with(|symbol| gl_context.get_proc_address(symbol) as *const _);
+        }
+
+        // declared after the GL context, so their GL objects are deleted while it still exists
+        let framebuffer = Framebuffer::new(width, height);
+
+        let resources = ContextResources::new(&session::default_resources(self.resources)?)?;
+
+        let mut session = HeadlessSession {
+            resources: &resources,
+            manager: self.manager,
+            framebuffer: framebuffer,
+            start_time: Instant::now(),
+        };
+
+        handler(&mut session);
+
+        Ok(())
+    }
+}
+
+impl HeadlessSession<'_> {
+    pub fn size(&self) -> (u32, u32) {
+        (self.framebuffer.width, self.framebuffer.height)
+    }
+
+    pub fn new_view(&self, path_to_json: &str) -> Result<View, LayoutError> {
+        self.framebuffer.bind();
+
+        session::load_view(
+            self.resources,
+            &self.manager,
+            self.size(),
+            1.0,
+            path_to_json,
+        )
+    }
+
+    pub fn draw(&mut self, view: &mut View, palette: &dyn frontend::Palette, hooks: &Hooks) {
+        let time = session::get_elapsed_time(&self.start_time);
+        self.draw_at(view, palette, hooks, time);
+    }
+
+    // renders with a fixed PresentationContext::time, so time-dependent components are deterministic
+    pub fn draw_at(
+        &mut self,
+        view: &mut View,
+        palette: &dyn frontend::Palette,
+        hooks: &Hooks,
+        time: f32,
+    ) {
+        self.framebuffer.bind();
+
+        session::render_view(
+            self.resources,
+            self.size(),
+            None,
+            1.0,
+            time,
+            view,
+            palette,
+            hooks,
+            None,
+        );
+    }
+
+    // contents of the last drawn frame
+    pub fn snapshot(&self) -> Snapshot {
+        self.framebuffer.read()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.snapshot().save_png(path)
+    }
+}
@@ -0,0 +1,90 @@
+extern crate glutin;
+
+use nalgebra::Vector2;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PointerButton {
+    Left,
+    Right,
+    Middle,
+    Other(u8),
+}
+
+// Pointer positions are in the same coordinates as DrawZone, scroll deltas in lines
+// (positive = away from the user).
+#[derive(Clone, Debug, PartialEq)]
+pub enum InputEvent {
+    PointerMoved(Vector2<f32>),
+    PointerDown(Vector2<f32>, PointerButton),
+    PointerUp(Vector2<f32>, PointerButton),
+    Scroll(Vector2<f32>, f32),
+    KeyDown(glutin::VirtualKeyCode),
+    KeyUp(glutin::VirtualKeyCode),
+    Character(char),
+}
+
+impl InputEvent {
+    pub fn position(&self) -> Option<Vector2<f32>> {
+        match self {
+            InputEvent::PointerMoved(p)
+            | InputEvent::PointerDown(p, _)
+            | InputEvent::PointerUp(p, _)
+            | InputEvent::Scroll(p, _) => Some(*p),
+            _ => None,
+        }
+    }
+}
+
+// emitted by components in response to input, Custom carries a component-defined name
+#[derive(Clone, Debug, PartialEq)]
+pub enum OutputEvent {
+    Clicked,
+    ValueChanged(f32),
+    Custom(String),
+}
+
+const PIXELS_PER_SCROLL_LINE: f32 = 20.0;
+
+// cursor is updated on cursor moves, pointer events without position are reported at it
+pub(crate) fn from_glutin(
+    event: &glutin::WindowEvent,
+    cursor: &mut Vector2<f32>,
+) -> Option<InputEvent> {
+    match event {
+        glutin::WindowEvent::CursorMoved { position, .. } => {
+            *cursor = Vector2::new(position.0 as f32, position.1 as f32);
+            Some(InputEvent::PointerMoved(*cursor))
+        }
+        glutin::WindowEvent::MouseInput { state, button, .. } => {
+            let button = match button {
+                glutin::MouseButton::Left => PointerButton::Left,
+                glutin::MouseButton::Right => PointerButton::Right,
+                glutin::MouseButton::Middle => PointerButton::Middle,
+                glutin::MouseButton::Other(n) => PointerButton::Other(*n),
+            };
+
+            match state {
+                glutin::ElementState::Pressed => Some(InputEvent::PointerDown(*cursor, button)),
+                glutin::ElementState::Released => Some(InputEvent::PointerUp(*cursor, button)),
+            }
+        }
+        glutin::WindowEvent::MouseWheel { delta, .. } => {
+            let lines = match delta {
+                glutin::MouseScrollDelta::LineDelta(_, y) => *y,
+                glutin::MouseScrollDelta::PixelDelta(_, y) => *y / PIXELS_PER_SCROLL_LINE,
+            };
+
+            Some(InputEvent::Scroll(*cursor, lines))
+        }
+        glutin::WindowEvent::KeyboardInput { input, .. } => {
+            let key = input.virtual_keycode?;
+
+            match input.state {
+                glutin::ElementState::Pressed => Some(InputEvent::KeyDown(key)),
+                glutin::ElementState::Released => Some(InputEvent::KeyUp(key)),
+            }
+        }
+        glutin::WindowEvent::ReceivedCharacter(c) => Some(InputEvent::Character(*c)),
+        _ => None,
+    }
+}
@@ -1,14 +1,28 @@
 pub mod basic_components;
 pub mod frontend;
 pub mod geometry_components;
+pub mod golden;
+pub mod headless;
+pub mod input;
+pub mod perf;
+pub mod resources;
 pub mod session;
+pub mod watch;
+pub mod window;
 
 use nalgebra::Vector2;
 use serde;
 use serde_json;
-use std::collections::HashMap;
+use std::any::Any;
+use std::cell::{Cell, Ref, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::error;
+use std::fmt;
 use std::fs;
+use std::io;
 use std::rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 //RunTime Parametric Structures
 
@@ -54,6 +68,10 @@ impl DrawZone {
         self.size.x / self.size.y
     }
 
+    pub fn contains(&self, p: Vector2<f32>) -> bool {
+        p.x >= self.left() && p.x <= self.right() && p.y >= self.bottom() && p.y <= self.top()
+    }
+
     pub fn constraint_to_aspect(&self, aspect: Option<f32>) -> DrawZone {
         match aspect {
             Some(aspect) => DrawZone {
@@ -101,6 +119,38 @@ where
         internal_data: &mut TComponentInternalInstanceData,
         public_data: &TComponentPublicInstanceData,
     );
+
+    // Pointer events are delivered to the innermost component whose last draw zone contains
+    // the pointer, except for pointer up, which goes to the component that received the
+    // pointer down. Key events go to the component that received the last pointer down.
+    // Handled events redraw the view, except pointer moves that emit no event.
+    fn handle_event(
+        &self,
+        __event: &input::InputEvent,
+        __zone: DrawZone,
+        __internal_data: &mut TComponentInternalInstanceData,
+        __public_data: &TComponentPublicInstanceData,
+        __emit: &mut dyn FnMut(input::OutputEvent),
+    ) {
+    }
+
+    // named fonts and images the component will look up in its data
+    fn resources(
+        &self,
+        __public_data: &TComponentPublicInstanceData,
+    ) -> Vec<resources::ResourceRef> {
+        Vec::new()
+    }
+
+    // Whether the drawing depends on PresentationContext::time (i.e. blinking). Checked after
+    // each draw, the view keeps being redrawn while it's true even if no data changed.
+    fn is_animated(
+        &self,
+        __internal_data: &TComponentInternalInstanceData,
+        __public_data: &TComponentPublicInstanceData,
+    ) -> bool {
+        false
+    }
 }
 
 type WrappedInit = Box<
@@ -108,34 +158,609 @@ type WrappedInit = Box<
         &mut frontend::PresentationContext,
         &serde_json::Value,
         usize,
-    ) -> Option<WrappedDraw>,
+        &str,
+    ) -> Result<WrappedInstance, LayoutError>,
 >;
 
+// The public data is passed to the wrapped closures as &RefCell<TComponentPublicInstanceData>,
+// so that a reloaded tree can take over the data of typed instances, see View::adopt.
 type WrappedDraw = Box<
     dyn FnMut(
         &mut frontend::PresentationContext,
         DrawZone,
         &mut [DrawChild],
+        &dyn Any,
         &serde_json::Map<String, serde_json::Value>,
-    ),
+        bool, // the public data changed since the last draw
+    ) -> bool, // is_animated
+>;
+type WrappedHandleEvent =
+    Box<dyn FnMut(&input::InputEvent, DrawZone, &dyn Any, &mut dyn FnMut(input::OutputEvent))>;
+type WrappedSerialize = Box<dyn Fn(&dyn Any) -> serde_json::Value>;
+type WrappedCheckHooks = Box<
+    dyn Fn(&dyn Any, &serde_json::Map<String, serde_json::Value>) -> Result<(), serde_json::Error>,
 >;
+type WrappedApplyHooks = WrappedCheckHooks;
+type WrappedAssign = Box<dyn Fn(&dyn Any, &dyn Any) -> bool>; // from, to; false if types differ
+
+fn downcast_public_data<T: 'static>(public_data: &dyn Any) -> &RefCell<T> {
+    match public_data.downcast_ref::<RefCell<T>>() {
+        Some(public_data) => public_data,
+        None => panic!("public data of another component type"),
+    }
+}
+
+struct WrappedInstance {
+    draw: WrappedDraw,
+    handle_event: WrappedHandleEvent,
+    public_data: rc::Rc<dyn Any>, // rc::Rc<RefCell<TComponentPublicInstanceData>>
+    serialize: WrappedSerialize,
+    check_hooks: WrappedCheckHooks,
+    apply_hooks: WrappedApplyHooks, // writes the hooks into the public data
+    assign: WrappedAssign,
+    property_types: rc::Rc<HashMap<String, String>>, // expected type of each property
+}
+
 pub type Hooks = HashMap<String, serde_json::Map<String, serde_json::Value>>;
-pub type View = TreeComponent;
+
+// Typed handle to the public data of a named component instance. Unlike string-keyed hooks,
+// changes made through it are retained across frames and skip the JSON round trip.
+pub struct Instance<T> {
+    data: rc::Rc<RefCell<T>>,
+    changed: rc::Rc<Cell<bool>>, // shared with the tree, see View::needs_redraw
+    updated: rc::Rc<Cell<Option<Instant>>>, // shared with the tree, see View::is_stale
+}
+
+impl<T> Instance<T> {
+    pub fn get(&self) -> Ref<T> {
+        self.data.borrow()
+    }
+
+    pub fn update<F: FnOnce(&mut T)>(&self, f: F) {
+        f(&mut self.data.borrow_mut());
+        self.changed.set(true);
+        self.updated.set(Some(Instant::now()));
+    }
+
+    pub fn set(&self, data: T) {
+        *self.data.borrow_mut() = data;
+        self.changed.set(true);
+        self.updated.set(Some(Instant::now()));
+    }
+}
+
+impl<T> Clone for Instance<T> {
+    fn clone(&self) -> Instance<T> {
+        Instance {
+            data: rc::Rc::clone(&self.data),
+            changed: rc::Rc::clone(&self.changed),
+            updated: rc::Rc::clone(&self.updated),
+        }
+    }
+}
+
+// Every variant referring to a node of the layout carries its JSON pointer (RFC 6901),
+// i.e. "/children/0/children/2" for the third child of the first child of the root.
+#[derive(Debug)]
+pub enum LayoutError {
+    Io {
+        path: String,
+        source: io::Error,
+    },
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    MissingType {
+        pointer: String,
+    },
+    UnknownComponent {
+        pointer: String,
+        component: String,
+    },
+    TooManyChildren {
+        pointer: String,
+        component: &'static str,
+        max: u32,
+        found: usize,
+    },
+    InvalidData {
+        pointer: String,
+        component: &'static str,
+        message: String,
+    },
+    MissingResource {
+        pointer: String,
+        component: &'static str,
+        source: resources::ResourceError,
+    },
+}
+
+impl LayoutError {
+    // pointer of the offending node, "" (the whole document) for I/O and syntax errors
+    pub fn pointer(&self) -> &str {
+        match self {
+            LayoutError::Io { .. } | LayoutError::Syntax { .. } => "",
+            LayoutError::MissingType { pointer }
+            | LayoutError::UnknownComponent { pointer, .. }
+            | LayoutError::TooManyChildren { pointer, .. }
+            | LayoutError::InvalidData { pointer, .. }
+            | LayoutError::MissingResource { pointer, .. } => pointer,
+        }
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::Io { path, source } => {
+                write!(f, "failed to read layout '{}': {}", path, source)
+            }
+            LayoutError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "layout is not valid JSON (line {}, column {}): {}",
+                line, column, message
+            ),
+            LayoutError::MissingType { pointer } => {
+                write!(f, "node '{}' has no \"type\" string", pointer)
+            }
+            LayoutError::UnknownComponent { pointer, component } => write!(
+                f,
+                "node '{}' refers to unknown component type '{}'",
+                pointer, component
+            ),
+            LayoutError::TooManyChildren {
+                pointer,
+                component,
+                max,
+                found,
+            } => write!(
+                f,
+                "node '{}' ({}) has {} children, at most {} allowed",
+                pointer, component, found, max
+            ),
+            LayoutError::InvalidData {
+                pointer,
+                component,
+                message,
+            } => write!(
+                f,
+                "node '{}' ({}) has invalid data: {}",
+                pointer, component, message
+            ),
+            LayoutError::MissingResource {
+                pointer,
+                component,
+                source,
+            } => write!(f, "node '{}' ({}): {}", pointer, component, source),
+        }
+    }
+}
+
+impl error::Error for LayoutError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            LayoutError::Io { source, .. } => Some(source),
+            LayoutError::MissingResource { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum HookError {
+    UnknownComponent {
+        component: String,
+    },
+    UnknownProperty {
+        component: String,
+        component_type: &'static str,
+        property: String,
+    },
+    TypeMismatch {
+        component: String,
+        component_type: &'static str,
+        property: String,
+        expected: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for HookError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HookError::UnknownComponent { component } => {
+                write!(f, "no component named '{}' in the view", component)
+            }
+            HookError::UnknownProperty {
+                component,
+                component_type,
+                property,
+            } => write!(
+                f,
+                "component '{}' ({}) has no property '{}'",
+                component, component_type, property
+            ),
+            HookError::TypeMismatch {
+                component,
+                component_type,
+                property,
+                expected,
+                message,
+            } => write!(
+                f,
+                "property '{}' of component '{}' ({}) expects {}: {}",
+                property, component, component_type, expected, message
+            ),
+        }
+    }
+}
+
+impl error::Error for HookError {}
+
+pub struct HookTarget {
+    pub component: String,
+    pub component_type: &'static str,
+    pub properties: Vec<(String, String)>, // property name, expected type
+}
+
+// Expected type of every property of the public data, described from its JSON Schema, i.e.
+// "a number or null" for an Option<f32>.
+fn property_types<T: schemars::JsonSchema>() -> HashMap<String, String> {
+    let root = schemars::gen::SchemaGenerator::default().into_root_schema_for::<T>();
+
+    match &root.schema.object {
+        Some(object) => object
+            .properties
+            .iter()
+            .map(|(property, schema)| {
+                (
+                    property.clone(),
+                    describe_schema(schema, &root.definitions, 0),
+                )
+            })
+            .collect(),
+        None => HashMap::new(),
+    }
+}
+
+fn describe_schema(
+    schema: &schemars::schema::Schema,
+    definitions: &schemars::Map<String, schemars::schema::Schema>,
+    depth: u32,
+) -> String {
+    use schemars::schema::{InstanceType, Schema, SingleOrVec};
+
+    let object = match schema {
+        Schema::Object(object) => object,
+        Schema::Bool(_) => return "any value".to_string(),
+    };
+
+    // definitions are followed a few levels deep, recursive types end at their name
+    match &object.reference {
+        Some(reference) => {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+
+            return match (definitions.get(name), depth < 4) {
+                (Some(definition), true) => describe_schema(definition, definitions, depth + 1),
+                _ => name.to_string(),
+            };
+        }
+        None => {}
+    }
+
+    match &object.enum_values {
+        Some(values) => {
+            let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+            return format!("one of {}", values.join(", "));
+        }
+        None => {}
+    }
+
+    match &object.subschemas {
+        Some(subschemas) => {
+            let describe_all = |schemas: &Vec<Schema>| {
+                schemas
+                    .iter()
+                    .map(|schema| describe_schema(schema, definitions, depth + 1))
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            };
+
+            match (&subschemas.any_of, &subschemas.one_of, &subschemas.all_of) {
+                (Some(any_of), _, _) => return describe_all(any_of),
+                (None, Some(one_of), _) => return describe_all(one_of),
+                // a reference with metadata, i.e. a default value
+                (None, None, Some(all_of)) if all_of.len() == 1 => {
+                    return describe_schema(&all_of[0], definitions, depth + 1)
+                }
+                _ => {}
+            }
+        }
+        None => {}
+    }
+
+    let non_negative = match &object.number {
+        Some(number) => number.minimum == Some(0.0),
+        None => false,
+    };
+
+    let type_name = |instance_type: &InstanceType| match instance_type {
+        InstanceType::Null => "null",
+        InstanceType::Boolean => "a boolean",
+        InstanceType::Object => "an object",
+        InstanceType::Array => "an array",
+        InstanceType::Number => "a number",
+        InstanceType::String => "a string",
+        InstanceType::Integer if non_negative => "a non-negative integer",
+        InstanceType::Integer => "an integer",
+    };
+
+    match &object.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => type_name(instance_type).to_string(),
+        Some(SingleOrVec::Vec(instance_types)) => instance_types
+            .iter()
+            .map(type_name)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        None => "any value".to_string(),
+    }
+}
+
+// for properties missing in the schema
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "a boolean",
+        serde_json::Value::Number(_) => "a number",
+        serde_json::Value::String(_) => "a string",
+        serde_json::Value::Array(_) => "an array",
+        serde_json::Value::Object(_) => "an object",
+    }
+}
 
 pub struct TreeComponent {
     children: Vec<TreeComponent>,
     draw: WrappedDraw,
+    handle_event: WrappedHandleEvent,
+    last_zone: Option<DrawZone>,
+    public_data: rc::Rc<dyn Any>,
+    serialize: WrappedSerialize,
+    check_hooks: WrappedCheckHooks,
+    apply_hooks: WrappedApplyHooks,
+    assign: WrappedAssign,
+    layout_data: serde_json::Value, // public data as built from the layout
+    component_type: &'static str,
     name: Option<String>,
+    animated: bool,
+    changed: rc::Rc<Cell<bool>>, // through an Instance since the last draw
+    updated: rc::Rc<Cell<Option<Instant>>>, // last change through an Instance
+    property_types: rc::Rc<HashMap<String, String>>,
 }
 
 impl TreeComponent {
-    pub fn draw(&mut self, ctx: &mut frontend::PresentationContext, zone: DrawZone, hooks: &Hooks) {
+    fn from_instance(
+        wrapped_instance: WrappedInstance,
+        children: Vec<TreeComponent>,
+        component_type: &'static str,
+        name: Option<String>,
+    ) -> TreeComponent {
+        let layout_data = (wrapped_instance.serialize)(&*wrapped_instance.public_data);
+
+        TreeComponent {
+            children: children,
+            draw: wrapped_instance.draw,
+            handle_event: wrapped_instance.handle_event,
+            last_zone: None,
+            public_data: wrapped_instance.public_data,
+            serialize: wrapped_instance.serialize,
+            check_hooks: wrapped_instance.check_hooks,
+            apply_hooks: wrapped_instance.apply_hooks,
+            assign: wrapped_instance.assign,
+            layout_data: layout_data,
+            component_type: component_type,
+            name: name,
+            animated: false,
+            changed: rc::Rc::new(Cell::new(false)),
+            updated: rc::Rc::new(Cell::new(None)),
+            property_types: wrapped_instance.property_types,
+        }
+    }
+
+    pub fn find(&self, name: &str) -> Option<&TreeComponent> {
+        if self.name.as_ref().map(|s| s.as_str()) == Some(name) {
+            return Some(self);
+        }
+
+        self.children.iter().filter_map(|child| child.find(name)).next()
+    }
+
+    // None if there is no instance of that name or its public data is not of type T
+    pub fn instance<T: 'static>(&self, name: &str) -> Option<Instance<T>> {
+        let component = self.find(name)?;
+        let public_data = rc::Rc::clone(&component.public_data);
+
+        match public_data.downcast::<RefCell<T>>() {
+            Ok(data) => Some(Instance {
+                data: data,
+                changed: rc::Rc::clone(&component.changed),
+                updated: rc::Rc::clone(&component.updated),
+            }),
+            Err(_) => None,
+        }
+    }
+
+    pub fn component_type(&self) -> &'static str {
+        self.component_type
+    }
+
+    // a value is accepted if the public data with it applied still deserializes
+    pub fn validate_hook(&self, property: &str, value: &serde_json::Value) -> Result<(), HookError> {
+        self.with_hook(property, value, &self.check_hooks)
+    }
+
+    // validates the value like validate_hook and writes it into the public data
+    fn apply_hook(&self, property: &str, value: &serde_json::Value) -> Result<(), HookError> {
+        self.with_hook(property, value, &self.apply_hooks)?;
+        self.changed.set(true);
+        Ok(())
+    }
+
+    // back to the value of the property in the layout
+    fn restore(&self, property: &str) {
+        match self.layout_data.get(property) {
+            Some(value) => match self.apply_hook(property, value) {
+                Ok(()) => {}
+                Err(er) => println!("Error while restoring '{}': {}", property, er),
+            },
+            None => {}
+        }
+    }
+
+    fn with_hook(
+        &self,
+        property: &str,
+        value: &serde_json::Value,
+        f: &WrappedCheckHooks,
+    ) -> Result<(), HookError> {
+        let component = self.name.clone().unwrap_or_default();
+        let current = (self.serialize)(&*self.public_data);
+
+        let expected = match current.get(property) {
+            Some(current_value) => self.expected_type(property, current_value),
+            None => {
+                return Err(HookError::UnknownProperty {
+                    component: component,
+                    component_type: self.component_type,
+                    property: property.to_string(),
+                })
+            }
+        };
+
+        let mut hooks = serde_json::Map::new();
+        hooks.insert(property.to_string(), value.clone());
+
+        f(&*self.public_data, &hooks).map_err(|er| HookError::TypeMismatch {
+            component: component,
+            component_type: self.component_type,
+            property: property.to_string(),
+            expected: expected,
+            message: er.to_string(),
+        })
+    }
+
+    fn expected_type(&self, property: &str, current_value: &serde_json::Value) -> String {
+        match self.property_types.get(property) {
+            Some(expected) => expected.clone(),
+            None => json_type_name(current_value).to_string(),
+        }
+    }
+
+    // child indices leading to the innermost component drawn under p
+    fn hit_path(&self, p: Vector2<f32>) -> Option<Vec<usize>> {
+        match self.last_zone {
+            Some(zone) if zone.contains(p) => {}
+            _ => return None,
+        }
+
+        // later children are drawn on top
+        for (i, child) in self.children.iter().enumerate().rev() {
+            match child.hit_path(p) {
+                Some(mut path) => {
+                    path.insert(0, i);
+                    return Some(path);
+                }
+                None => {}
+            }
+        }
+
+        Some(Vec::new())
+    }
+
+    fn at_path_mut(&mut self, path: &[usize]) -> Option<&mut TreeComponent> {
+        match path.split_first() {
+            Some((i, rest)) => self.children.get_mut(*i)?.at_path_mut(rest),
+            None => Some(self),
+        }
+    }
+
+    fn collect_hook_targets(&self, targets: &mut Vec<HookTarget>) {
+        match &self.name {
+            Some(name) => {
+                let properties = match (self.serialize)(&*self.public_data) {
+                    serde_json::Value::Object(fields) => fields
+                        .iter()
+                        .map(|(property, value)| {
+                            (property.clone(), self.expected_type(property, value))
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                };
+
+                targets.push(HookTarget {
+                    component: name.clone(),
+                    component_type: self.component_type,
+                    properties: properties,
+                });
+            }
+            None => {}
+        }
+
+        for child in &self.children {
+            child.collect_hook_targets(targets);
+        }
+    }
+
+    // Components keeping their name and type take over the public data of the previous tree,
+    // overwritten with their new data, so typed instances stay bound.
+    fn adopt_instances(&mut self, previous: &TreeComponent) {
+        match &self.name {
+            Some(name) => match previous.find(name) {
+                Some(old) if old.component_type == self.component_type => {
+                    match (self.assign)(&*self.public_data, &*old.public_data) {
+                        true => {
+                            self.public_data = rc::Rc::clone(&old.public_data);
+                            self.changed = rc::Rc::clone(&old.changed);
+                            self.updated = rc::Rc::clone(&old.updated);
+                            self.changed.set(true);
+                        }
+                        false => {}
+                    }
+                }
+                _ => {}
+            },
+            None => {}
+        }
+
+        for child in &mut self.children {
+            child.adopt_instances(previous);
+        }
+    }
+
+    fn needs_redraw(&self) -> bool {
+        self.animated || self.changed.get() || self.children.iter().any(|c| c.needs_redraw())
+    }
+
+    fn draw(
+        &mut self,
+        ctx: &mut frontend::PresentationContext,
+        zone: DrawZone,
+        hooks: &Hooks,
+        stale: &HashSet<String>,
+    ) {
+        self.last_zone = Some(zone);
+
         let mut draws: Vec<Box<dyn FnMut(&mut frontend::PresentationContext, DrawZone) -> DrawZone>> =
             Vec::new();
         for child in &mut self.children {
             let b = Box::new(
                 move |ctx: &mut frontend::PresentationContext, z: DrawZone| -> DrawZone {
-                    child.draw(ctx, z, hooks);
+                    child.draw(ctx, z, hooks, stale);
                     DrawZone::from_rect(Vector2::new(0.0, 0.0),Vector2::new(0.0, 0.0))
                 },
             );
@@ -152,29 +777,505 @@ impl TreeComponent {
             None => &no_hooks,
         };
 
-        self.draw.as_mut()(ctx, zone, &mut draws[..], my_hooks);
+        self.animated = self.draw.as_mut()(
+            ctx,
+            zone,
+            &mut draws[..],
+            &*self.public_data,
+            my_hooks,
+            self.changed.get(),
+        );
+        self.changed.set(false);
+
+        match &self.name {
+            Some(name) if stale.contains(name) => draw_stale_indication(ctx, zone),
+            _ => {}
+        }
     }
 }
 
+// crossed-out frame over a component whose data timed out
+fn draw_stale_indication(ctx: &mut frontend::PresentationContext, zone: DrawZone) {
+    let color = ctx
+        .resources
+        .palette
+        .status_to_color(frontend::Status::Warning);
+
+    ctx.frame.path(
+        |mut path| {
+            path.rect((zone.left(), zone.bottom()), (zone.size.x, zone.size.y));
+            path.move_to((zone.left(), zone.bottom()));
+            path.line_to((zone.right(), zone.top()));
+            path.move_to((zone.right(), zone.bottom()));
+            path.line_to((zone.left(), zone.top()));
+            path.stroke(
+                color,
+                nanovg::StrokeOptions {
+                    width: 3.0,
+                    ..Default::default()
+                },
+            );
+        },
+        Default::default(),
+    );
+}
+
+// A loaded layout together with its retained hook state. Values set on the view are written
+// into the public data of their component once and persist until they are changed or
+// cleared; hooks passed to draw are applied on top of them.
+pub struct View {
+    root: TreeComponent,
+    retained: HashMap<String, HashMap<String, serde_json::Value>>,
+    updated: HashMap<String, HashMap<String, Instant>>, // by set or hooks, see is_stale
+    stale_timeouts: HashMap<String, HashMap<String, Duration>>,
+    focus: Option<Vec<usize>>,
+    events: Vec<(String, input::OutputEvent)>,
+    checked_hooks: HashMap<String, HashSet<String>>, // per-frame hooks validated so far
+    hook_errors: Vec<HookError>,
+    id: usize,
+    screen: Option<session::ScreenId>, // the window's context it was built with, if any
+    invalidated: bool,
+    last_hooks: Option<Hooks>, // per-frame hooks of the last draw
+    last_stale: HashSet<String>,
+}
+
+static NEXT_VIEW_ID: AtomicUsize = AtomicUsize::new(0);
+
+impl View {
+    fn new(root: TreeComponent) -> View {
+        View {
+            root: root,
+            retained: HashMap::new(),
+            updated: HashMap::new(),
+            stale_timeouts: HashMap::new(),
+            focus: None,
+            events: Vec::new(),
+            checked_hooks: HashMap::new(),
+            hook_errors: Vec::new(),
+            id: NEXT_VIEW_ID.fetch_add(1, Ordering::Relaxed),
+            screen: None,
+            invalidated: true,
+            last_hooks: None,
+            last_stale: HashSet::new(),
+        }
+    }
+
+    // Routes the event to its target component (see Component::handle_event). Events it
+    // emits are queued for drain_events, tagged with its name.
+    pub fn handle_event(&mut self, event: &input::InputEvent) {
+        let target = match event {
+            input::InputEvent::PointerDown(p, _) => {
+                self.focus = self.root.hit_path(*p);
+                self.focus.clone()
+            }
+            input::InputEvent::PointerUp(p, _) => match self.focus {
+                Some(ref focus) => Some(focus.clone()),
+                None => self.root.hit_path(*p),
+            },
+            input::InputEvent::PointerMoved(p) | input::InputEvent::Scroll(p, _) => {
+                self.root.hit_path(*p)
+            }
+            _ => self.focus.clone(),
+        };
+
+        let component = match target {
+            Some(path) => self.root.at_path_mut(&path),
+            None => None,
+        };
+
+        match component {
+            Some(component) => match component.last_zone {
+                // unnamed components can't be told apart by the application, so their events
+                // are dropped
+                Some(zone) => {
+                    let mut emitted = false;
+
+                    match component.name {
+                        Some(ref name) => {
+                            let events = &mut self.events;
+                            component.handle_event.as_mut()(
+                                event,
+                                zone,
+                                &*component.public_data,
+                                &mut |output| {
+                                    emitted = true;
+                                    events.push((name.clone(), output))
+                                },
+                            );
+                        }
+                        None => component.handle_event.as_mut()(
+                            event,
+                            zone,
+                            &*component.public_data,
+                            &mut |_| emitted = true,
+                        ),
+                    }
+
+                    // The component may have changed its internal state. Plain pointer moves
+                    // are too frequent to redraw for, unless the component reacted to them.
+                    match event {
+                        input::InputEvent::PointerMoved(_) if !emitted => {}
+                        _ => self.invalidated = true,
+                    }
+                }
+                None => {}
+            },
+            None => {}
+        }
+    }
+
+    // Events emitted by components since the last call, in the order they happened.
+    pub fn drain_events(&mut self) -> Vec<(String, input::OutputEvent)> {
+        std::mem::replace(&mut self.events, Vec::new())
+    }
+
+    // checks a hook against the public data of the named component
+    pub fn validate_hook(
+        &self,
+        component: &str,
+        property: &str,
+        value: &serde_json::Value,
+    ) -> Result<(), HookError> {
+        match self.root.find(component) {
+            Some(target) => target.validate_hook(property, value),
+            None => Err(HookError::UnknownComponent {
+                component: component.to_string(),
+            }),
+        }
+    }
+
+    pub fn validate_hooks(&self, hooks: &Hooks) -> Result<(), HookError> {
+        for (component, properties) in hooks {
+            for (property, value) in properties {
+                self.validate_hook(component, property, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // all named components and the properties their hooks can target
+    pub fn hook_targets(&self) -> Vec<HookTarget> {
+        let mut targets = Vec::new();
+        self.root.collect_hook_targets(&mut targets);
+        targets
+    }
+
+    pub fn find(&self, name: &str) -> Option<&TreeComponent> {
+        self.root.find(name)
+    }
+
+    pub fn instance<T: 'static>(&self, name: &str) -> Option<Instance<T>> {
+        self.root.instance(name)
+    }
+
+    pub fn set<T>(&mut self, component: &str, property: &str, value: T) -> Result<(), HookError>
+    where
+        T: serde::ser::Serialize,
+    {
+        self.retain(component, property, serde_json::json!(value))
+    }
+
+    fn retain(
+        &mut self,
+        component: &str,
+        property: &str,
+        value: serde_json::Value,
+    ) -> Result<(), HookError> {
+        match self.root.find(component) {
+            Some(target) => target.apply_hook(property, &value)?,
+            None => {
+                return Err(HookError::UnknownComponent {
+                    component: component.to_string(),
+                })
+            }
+        }
+
+        self.retained
+            .entry(component.to_string())
+            .or_insert_with(HashMap::new)
+            .insert(property.to_string(), value);
+        self.touch(component, property);
+
+        Ok(())
+    }
+
+    fn touch(&mut self, component: &str, property: &str) {
+        self.updated
+            .entry(component.to_string())
+            .or_insert_with(HashMap::new)
+            .insert(property.to_string(), Instant::now());
+    }
+
+    // retains every hook of the map, nothing is retained if any of them is invalid
+    pub fn set_hooks(&mut self, hooks: &Hooks) -> Result<(), HookError> {
+        self.validate_hooks(hooks)?;
+
+        for (component, properties) in hooks {
+            for (property, value) in properties {
+                self.retain(component, property, value.clone())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // The property falls back to the data from the layout, overwriting changes made through
+    // a typed instance.
+    pub fn clear(&mut self, component: &str, property: &str) {
+        let removed = match self.retained.get_mut(component) {
+            Some(properties) => properties.remove(property).is_some(),
+            None => false,
+        };
+
+        match (removed, self.root.find(component)) {
+            (true, Some(target)) => target.restore(property),
+            _ => {}
+        }
+    }
+
+    pub fn clear_all(&mut self) {
+        for (component, properties) in self.retained.drain() {
+            match self.root.find(&component) {
+                Some(target) => {
+                    for property in properties.keys() {
+                        target.restore(property);
+                    }
+                }
+                None => {}
+            }
+        }
+    }
+
+    // A property not updated within the timeout (or never at all) marks its component as
+    // stale, which is drawn crossed out. Setting it on the view, passing it in the hooks of
+    // draw or updating the component through a typed instance (which counts for all of its
+    // properties) refreshes it. None removes the timeout.
+    pub fn set_stale_timeout(&mut self, component: &str, property: &str, timeout: Option<Duration>) {
+        let timeouts = self
+            .stale_timeouts
+            .entry(component.to_string())
+            .or_insert_with(HashMap::new);
+
+        match timeout {
+            Some(timeout) => {
+                timeouts.insert(property.to_string(), timeout);
+            }
+            None => {
+                timeouts.remove(property);
+            }
+        }
+    }
+
+    pub fn is_stale(&self, component: &str) -> bool {
+        self.is_stale_hooked(component, None)
+    }
+
+    // properties in the hooks of the coming draw are fresh
+    fn is_stale_hooked(
+        &self,
+        component: &str,
+        hooks: Option<&serde_json::Map<String, serde_json::Value>>,
+    ) -> bool {
+        let timeouts = match self.stale_timeouts.get(component) {
+            Some(timeouts) => timeouts,
+            None => return false,
+        };
+
+        let instance_updated = match self.root.find(component) {
+            Some(target) => target.updated.get(),
+            None => None,
+        };
+
+        timeouts.iter().any(|(property, timeout)| {
+            match hooks {
+                Some(hooks) if hooks.contains_key(property) => return false,
+                _ => {}
+            }
+
+            let updated = self
+                .updated
+                .get(component)
+                .and_then(|properties| properties.get(property))
+                .cloned();
+
+            match updated.max(instance_updated) {
+                Some(updated) => updated.elapsed() > *timeout,
+                None => true,
+            }
+        })
+    }
+
+    // components of the tree with per-frame or retained hooks
+    pub fn hooked_components(&self, hooks: &Hooks) -> usize {
+        let names: HashSet<&String> = hooks.keys().chain(self.retained.keys()).collect();
+
+        names
+            .into_iter()
+            .filter(|name| self.root.find(name).is_some())
+            .count()
+    }
+
+    // forces the next frame to be drawn, i.e. after switching palettes
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
+    // Whether drawing with these hooks would differ from the last frame: a hook changed, a
+    // retained value or typed instance was updated, staleness changed, an input event was
+    // handled (see Component::handle_event) or a component is animated.
+    pub fn needs_redraw(&self, hooks: &Hooks) -> bool {
+        self.invalidated
+            || self.last_hooks.as_ref() != Some(hooks)
+            || self.stale_components(hooks) != self.last_stale
+            || self.root.needs_redraw()
+    }
+
+    fn stale_components(&self, hooks: &Hooks) -> HashSet<String> {
+        self.stale_timeouts
+            .keys()
+            .filter(|component| self.is_stale_hooked(component, hooks.get(*component)))
+            .cloned()
+            .collect()
+    }
+
+    // per-frame hooks refresh the properties with a staleness timeout
+    fn touch_hooked(&mut self, hooks: &Hooks) {
+        let now = Instant::now();
+
+        for (component, timeouts) in &self.stale_timeouts {
+            let properties = match hooks.get(component) {
+                Some(properties) => properties,
+                None => continue,
+            };
+
+            for property in timeouts.keys() {
+                match properties.contains_key(property) {
+                    true => {
+                        self.updated
+                            .entry(component.clone())
+                            .or_insert_with(HashMap::new)
+                            .insert(property.clone(), now);
+                    }
+                    false => {}
+                }
+            }
+        }
+    }
+
+    // Per-frame hooks are validated like values set on the view the first time a property of
+    // a component shows up in them. Errors are printed and queued for drain_hook_errors.
+    fn check_new_hooks(&mut self, hooks: &Hooks) {
+        for (component, properties) in hooks {
+            for (property, value) in properties {
+                let checked = match self.checked_hooks.get(component) {
+                    Some(checked) => checked.contains(property),
+                    None => false,
+                };
+
+                if checked {
+                    continue;
+                }
+
+                match self.validate_hook(component, property, value) {
+                    Ok(()) => {}
+                    Err(er) => {
+                        println!("Invalid hook: {}", er);
+                        self.hook_errors.push(er);
+                    }
+                }
+
+                self.checked_hooks
+                    .entry(component.clone())
+                    .or_insert_with(HashSet::new)
+                    .insert(property.clone());
+            }
+        }
+    }
+
+    // Errors of per-frame hooks passed to draw since the last call, see check_new_hooks.
+    pub fn drain_hook_errors(&mut self) -> Vec<HookError> {
+        std::mem::replace(&mut self.hook_errors, Vec::new())
+    }
+
+    pub fn draw(&mut self, ctx: &mut frontend::PresentationContext, zone: DrawZone, hooks: &Hooks) {
+        self.check_new_hooks(hooks);
+        self.touch_hooked(hooks);
+        let stale = self.stale_components(hooks);
+
+        self.root.draw(ctx, zone, hooks, &stale);
+
+        self.invalidated = false;
+        self.last_stale = stale;
+
+        if self.last_hooks.as_ref() != Some(hooks) {
+            self.last_hooks = Some(hooks.clone());
+        }
+    }
+
+    // Takes over the state of the view this one replaces (see WatchedView): the public data
+    // of typed instances, retained values still valid for the new layout, the times they
+    // were updated and the staleness timeouts.
+    pub(crate) fn adopt(&mut self, previous: View) {
+        self.root.adopt_instances(&previous.root);
+
+        for (component, properties) in previous.retained {
+            for (property, value) in properties {
+                match self.retain(&component, &property, value) {
+                    Ok(()) => {}
+                    Err(er) => println!("Dropping retained value: {}", er),
+                }
+            }
+        }
+
+        self.updated = previous.updated;
+        self.stale_timeouts = previous.stale_timeouts;
+    }
+
+    // identifies the view drawn last on a screen
+    pub(crate) fn id(&self) -> usize {
+        self.id
+    }
+
+    // font and image handles of the view belong to the screen's nanovg context
+    pub(crate) fn screen(&self) -> Option<session::ScreenId> {
+        self.screen
+    }
+
+    pub(crate) fn set_screen(&mut self, screen: session::ScreenId) {
+        self.screen = Some(screen);
+    }
+}
+
+type WrappedSchema = Box<dyn Fn(&mut schemars::gen::SchemaGenerator) -> serde_json::Value>;
+
 pub struct Manager {
     controls_types: HashMap<&'static str, WrappedInit>,
+    schemas: HashMap<&'static str, WrappedSchema>,
 }
 
 impl Manager {
-    fn join_hooks<T>(value: &T, hooks: &serde_json::Map<String, serde_json::Value>) -> T
+    fn try_join_hooks<T>(
+        value: &T,
+        hooks: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<T, serde_json::Error>
     where
         T: serde::ser::Serialize + serde::de::DeserializeOwned + Clone + 'static,
     {
-        let mut serialized = match serde_json::to_value(value) {
-            Ok(serialized) => serialized,
-            _ => return value.clone(),
-        };
+        let mut serialized = serde_json::to_value(value)?;
 
         for hook in hooks {
             serialized[hook.0] = hook.1.clone();
         }
 
-        match serde_json::from_value(serialized) {
+        serde_json::from_value(serialized)
+    }
+
+    fn join_hooks<T>(value: &T, hooks: &serde_json::Map<String, serde_json::Value>) -> T
+    where
+        T: serde::ser::Serialize + serde::de::DeserializeOwned + Clone + 'static,
+    {
+        match Manager::try_join_hooks(value, hooks) {
             Ok(object) => object,
             Err(er) => {
                 println!("Error while applying hook: {}", er);
@@ -189,101 +1290,332 @@ impl Manager {
         children_n: usize,
         public_data: T1,
         size_preference: f32,
-    ) -> WrappedDraw
+        pointer: &str,
+    ) -> Result<WrappedInstance, LayoutError>
     where
-        T1: serde::ser::Serialize + serde::de::DeserializeOwned + Clone + 'static,
+        T1: serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + schemars::JsonSchema
+            + Clone
+            + 'static,
         T2: 'static,
     {
-        let mut internal_data = component_type
+        match component_type.max_children() {
+            Some(max) if children_n > (max as usize) => {
+                return Err(LayoutError::TooManyChildren {
+                    pointer: pointer.to_string(),
+                    component: component_type.get_name(),
+                    max: max,
+                    found: children_n,
+                })
+            }
+            _ => {}
+        }
+
+        for resource in component_type.resources(&public_data) {
+            match ctx.resources.loaded.check(&resource) {
+                Ok(()) => {}
+                Err(er) => {
+                    return Err(LayoutError::MissingResource {
+                        pointer: pointer.to_string(),
+                        component: component_type.get_name(),
+                        source: er,
+                    })
+                }
+            }
+        }
+
+        let internal_data = component_type
             .as_ref()
             .as_ref()
             .init_instance(ctx, &public_data);
 
-        match component_type.max_children() {
-            Some(max) => assert!(children_n <= (max as usize)),
-            None => {}
-        }
+        Ok(Manager::wrap_instance(
+            component_type,
+            internal_data,
+            public_data,
+        ))
+    }
+
+    fn wrap_instance<T1, T2>(
+        component_type: std::rc::Rc<Box<dyn Component<T1, T2>>>,
+        internal_data: T2,
+        public_data: T1,
+    ) -> WrappedInstance
+    where
+        T1: serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + schemars::JsonSchema
+            + Clone
+            + 'static,
+        T2: 'static,
+    {
+        let internal_data = rc::Rc::new(RefCell::new(internal_data));
+        let __internal_data = rc::Rc::clone(&internal_data);
 
-        Box::new(
+        let public_data = rc::Rc::new(RefCell::new(public_data));
+        let __component_type = rc::Rc::clone(&component_type);
+
+        // the hooks of the last draw and the public data with them applied
+        let mut hooked: Option<(serde_json::Map<String, serde_json::Value>, T1)> = None;
+
+        let draw = Box::new(
             move |ctx: &mut frontend::PresentationContext,
                   zone: DrawZone,
                   children: &mut [DrawChild],
-                  my_hooks: &serde_json::Map<String, serde_json::Value>| {
+                  public_data: &dyn Any,
+                  my_hooks: &serde_json::Map<String, serde_json::Value>,
+                  data_changed: bool| {
+                let public_data = downcast_public_data::<T1>(public_data).borrow();
+                let mut internal_data = __internal_data.borrow_mut();
+
                 if my_hooks.len() == 0 {
-                    component_type.as_ref().as_ref().draw(
-                        ctx,
-                        zone,
-                        children,
-                        &mut internal_data,
-                        &public_data,
-                    );
-                } else {
-                    let merged_data = Manager::join_hooks(&public_data, my_hooks);
+                    hooked = None;
 
                     component_type.as_ref().as_ref().draw(
                         ctx,
                         zone,
                         children,
-                        &mut internal_data,
-                        &merged_data,
+                        &mut *internal_data,
+                        &*public_data,
                     );
+
+                    return component_type
+                        .as_ref()
+                        .as_ref()
+                        .is_animated(&*internal_data, &*public_data);
                 }
+
+                // the JSON round trip only runs when the hooks or the data changed
+                let outdated = match &hooked {
+                    Some((last_hooks, _)) => data_changed || last_hooks != my_hooks,
+                    None => true,
+                };
+
+                if outdated {
+                    let merged_data = Manager::join_hooks(&*public_data, my_hooks);
+                    hooked = Some((my_hooks.clone(), merged_data));
+                }
+
+                let merged_data = match &hooked {
+                    Some((_, merged_data)) => merged_data,
+                    None => &*public_data,
+                };
+
+                component_type.as_ref().as_ref().draw(
+                    ctx,
+                    zone,
+                    children,
+                    &mut *internal_data,
+                    merged_data,
+                );
+
+                component_type
+                    .as_ref()
+                    .as_ref()
+                    .is_animated(&*internal_data, merged_data)
             },
-        )
+        );
+
+        let handle_event = Box::new(
+            move |event: &input::InputEvent,
+                  zone: DrawZone,
+                  public_data: &dyn Any,
+                  emit: &mut dyn FnMut(input::OutputEvent)| {
+                __component_type.as_ref().as_ref().handle_event(
+                    event,
+                    zone,
+                    &mut *internal_data.borrow_mut(),
+                    &*downcast_public_data::<T1>(public_data).borrow(),
+                    emit,
+                );
+            },
+        );
+
+        WrappedInstance {
+            draw: draw,
+            handle_event: handle_event,
+            public_data: public_data,
+            property_types: rc::Rc::new(property_types::<T1>()),
+            serialize: Box::new(|public_data: &dyn Any| {
+                serde_json::to_value(&*downcast_public_data::<T1>(public_data).borrow())
+                    .unwrap_or(serde_json::Value::Null)
+            }),
+            check_hooks: Box::new(
+                |public_data: &dyn Any, hooks: &serde_json::Map<String, serde_json::Value>| {
+                    let public_data = downcast_public_data::<T1>(public_data);
+                    Manager::try_join_hooks(&*public_data.borrow(), hooks).map(|_| ())
+                },
+            ),
+            apply_hooks: Box::new(
+                |public_data: &dyn Any,
+                 hooks: &serde_json::Map<String, serde_json::Value>|
+                 -> Result<(), serde_json::Error> {
+                    let public_data = downcast_public_data::<T1>(public_data);
+                    let joined = Manager::try_join_hooks(&*public_data.borrow(), hooks)?;
+                    *public_data.borrow_mut() = joined;
+                    Ok(())
+                },
+            ),
+            assign: Box::new(|from: &dyn Any, to: &dyn Any| {
+                match (
+                    from.downcast_ref::<RefCell<T1>>(),
+                    to.downcast_ref::<RefCell<T1>>(),
+                ) {
+                    (Some(from), Some(to)) => {
+                        *to.borrow_mut() = from.borrow().clone();
+                        true
+                    }
+                    _ => false,
+                }
+            }),
+        }
     }
 
     pub fn register_component_type<TComponentData, TPrivateComponentData>(
         &mut self,
         component: Box<dyn Component<TComponentData, TPrivateComponentData>>,
     ) where
-        TComponentData: serde::ser::Serialize + serde::de::DeserializeOwned + Clone + 'static,
+        TComponentData: serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + schemars::JsonSchema
+            + Clone
+            + 'static,
         TPrivateComponentData: 'static,
     {
         let stored_component = rc::Rc::new(component);
         let __stored_component = rc::Rc::clone(&stored_component);
+        let __stored_component_schema = rc::Rc::clone(&stored_component);
 
         let mk_wrapped_init = Box::new(
             move |ctx: &mut frontend::PresentationContext,
                   json: &serde_json::Value,
-                  children_n: usize|
-                  -> Option<WrappedDraw> {
+                  children_n: usize,
+                  pointer: &str|
+                  -> Result<WrappedInstance, LayoutError> {
                 let __stored_component2 = rc::Rc::clone(&__stored_component);
+                let invalid_data = |message: String| LayoutError::InvalidData {
+                    pointer: pointer.to_string(),
+                    component: __stored_component.get_name(),
+                    message: message,
+                };
 
                 let data = match TComponentData::deserialize(json) {
                     Ok(data) => data,
-                    Err(_) => {
-                        let default_data = __stored_component.as_ref().get_default_data()?;
-                        match json.as_object() {
-                            Some(hooks) => Manager::join_hooks(&default_data, hooks),
-                            None => default_data,
+                    Err(er) => {
+                        let default_data = match __stored_component.get_default_data() {
+                            Some(default_data) => default_data,
+                            None => return Err(invalid_data(er.to_string())),
+                        };
+
+                        match json {
+                            serde_json::Value::Object(hooks) => {
+                                Manager::try_join_hooks(&default_data, hooks)
+                                    .map_err(|er| invalid_data(er.to_string()))?
+                            }
+                            serde_json::Value::Null => default_data,
+                            _ => return Err(invalid_data(er.to_string())),
                         }
                     }
                 };
 
-                Some(Manager::mk_init(
+                Manager::mk_init(
                     ctx,
                     __stored_component2,
                     children_n,
                     data,
                     1.0,
-                ))
+                    pointer,
+                )
             },
         );
 
+        // schema of a layout node of this component type
+        let mk_schema = Box::new(move |gen: &mut schemars::gen::SchemaGenerator| {
+            let component = __stored_component_schema.as_ref();
+            let mut data_schema = TComponentData::json_schema(gen).into_object();
+
+            // data is merged with the default data if there is any, so no field is required
+            match component.get_default_data() {
+                Some(default_data) => {
+                    match data_schema.object.as_mut() {
+                        Some(object) => object.required.clear(),
+                        None => {}
+                    }
+
+                    data_schema.metadata().default = serde_json::to_value(default_data).ok()
+                }
+                None => {}
+            }
+
+            let mut children = serde_json::json!({
+                "type": "array",
+                "items": { "$ref": "#" }
+            });
+
+            match component.max_children() {
+                Some(max) => children["maxItems"] = serde_json::json!(max),
+                None => {}
+            }
+
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "type": { "const": component.get_name() },
+                    "name": { "type": "string" },
+                    "data": data_schema,
+                    "children": children
+                },
+                "required": ["type"]
+            })
+        });
+
         self.controls_types
             .insert(stored_component.as_ref().get_name(), mk_wrapped_init);
+        self.schemas
+            .insert(stored_component.as_ref().get_name(), mk_schema);
+    }
+
+    pub fn component_types(&self) -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = self.controls_types.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    // JSON Schema (draft 7) of layout files built from the registered components,
+    // with the public data of each component and its defaults
+    pub fn export_schema(&self) -> serde_json::Value {
+        let mut gen = schemars::gen::SchemaSettings::draft07().into_generator();
+
+        let nodes: Vec<serde_json::Value> = self
+            .component_types()
+            .iter()
+            .map(|name| self.schemas[name](&mut gen))
+            .collect();
+
+        serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "gaugen layout",
+            "oneOf": nodes,
+            "definitions": gen.definitions()
+        })
     }
 
     pub fn make_screen(
         &self,
         ctx: &mut frontend::PresentationContext,
         path_to_json: &str,
-    ) -> Option<View> {
-        let json = fs::read_to_string(path_to_json).unwrap();
-        let data: serde_json::Value = match serde_json::from_str(&json) {
-            Ok(data) => data,
-            Err(_) => return None,
-        };
+    ) -> Result<View, LayoutError> {
+        let json = fs::read_to_string(path_to_json).map_err(|er| LayoutError::Io {
+            path: path_to_json.to_string(),
+            source: er,
+        })?;
+        let data: serde_json::Value =
+            serde_json::from_str(&json).map_err(|er| LayoutError::Syntax {
+                line: er.line(),
+                column: er.column(),
+                message: er.to_string(),
+            })?;
 
         self.build_tree(ctx, &data)
     }
@@ -292,39 +1624,65 @@ impl Manager {
         &self,
         ctx: &mut frontend::PresentationContext,
         v: &serde_json::Value,
-    ) -> Option<View> {
-        let mk_init = &self.controls_types[v["type"].as_str()?];
+    ) -> Result<View, LayoutError> {
+        Ok(View::new(self.build_subtree(ctx, v, "")?))
+    }
+
+    fn build_subtree(
+        &self,
+        ctx: &mut frontend::PresentationContext,
+        v: &serde_json::Value,
+        pointer: &str,
+    ) -> Result<TreeComponent, LayoutError> {
+        let component = match v["type"].as_str() {
+            Some(component) => component,
+            None => {
+                return Err(LayoutError::MissingType {
+                    pointer: pointer.to_string(),
+                })
+            }
+        };
+
+        let (component_type_name, mk_init) = match self.controls_types.get_key_value(component) {
+            Some((component_type_name, mk_init)) => (*component_type_name, mk_init),
+            None => {
+                return Err(LayoutError::UnknownComponent {
+                    pointer: pointer.to_string(),
+                    component: component.to_string(),
+                })
+            }
+        };
 
         let mut children: Vec<TreeComponent> = Vec::new();
 
         match v["children"].as_array() {
             Some(json_children) => {
-                for json_child in json_children {
-                    let child_n_geometry = self.build_tree(ctx, json_child)?;
+                for (i, json_child) in json_children.iter().enumerate() {
+                    let child_pointer = format!("{}/children/{}", pointer, i);
+                    let child_n_geometry = self.build_subtree(ctx, json_child, &child_pointer)?;
                     children.push(child_n_geometry);
                 }
             }
             None => {}
         }
 
-        match mk_init(ctx, &v["data"], children.len()) {
-            Some(wrapped_draw) => Some(
-                TreeComponent {
-                    children: children,
-                    draw: wrapped_draw,
-                    name: match v["name"].as_str() {
-                        Some(s) => Some(s.to_string()),
-                        None => None,
-                    },
-                }
-            ),
-            None => None,
-        }
+        let wrapped_instance = mk_init(ctx, &v["data"], children.len(), pointer)?;
+
+        Ok(TreeComponent::from_instance(
+            wrapped_instance,
+            children,
+            component_type_name,
+            match v["name"].as_str() {
+                Some(s) => Some(s.to_string()),
+                None => None,
+            },
+        ))
     }
 
     pub fn new() -> Manager {
         Manager {
             controls_types: HashMap::new(),
+            schemas: HashMap::new(),
         }
     }
 }
@@ -344,3 +1702,158 @@ where
         hooks.insert(component.to_string(), properties);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone)]
+    struct TestData {
+        status: frontend::Status,
+        precision: u32,
+        limit: Option<f32>,
+    }
+
+    struct TestComponent {}
+
+    impl Component<TestData, ()> for TestComponent {
+        fn max_children(&self) -> Option<u32> {
+            Some(0)
+        }
+
+        fn get_name(&self) -> &'static str {
+            "Test"
+        }
+
+        fn get_default_data(&self) -> Option<TestData> {
+            None
+        }
+
+        fn init_instance(&self, __ctx: &mut frontend::PresentationContext, __data: &TestData) {}
+
+        fn draw(
+            &self,
+            __ctx: &mut frontend::PresentationContext,
+            __zone: DrawZone,
+            __children: &mut [DrawChild],
+            __internal_data: &mut (),
+            __public_data: &TestData,
+        ) {
+        }
+    }
+
+    // a view of a single component named "test", built without a presentation context
+    fn test_view() -> View {
+        let component: rc::Rc<Box<dyn Component<TestData, ()>>> =
+            rc::Rc::new(Box::new(TestComponent {}));
+        let data = TestData {
+            status: frontend::Status::Ok,
+            precision: 1,
+            limit: None,
+        };
+
+        View::new(TreeComponent::from_instance(
+            Manager::wrap_instance(component, (), data),
+            Vec::new(),
+            "Test",
+            Some("test".to_string()),
+        ))
+    }
+
+    fn is_type_mismatch(result: Result<(), HookError>) -> bool {
+        match result {
+            Err(HookError::TypeMismatch { .. }) => true,
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn validate_hook_rejects_an_unknown_variant_after_a_valid_one() {
+        let view = test_view();
+
+        assert!(view
+            .validate_hook("test", "status", &serde_json::json!("Warning"))
+            .is_ok());
+        assert!(is_type_mismatch(view.validate_hook(
+            "test",
+            "status",
+            &serde_json::json!("Bogus")
+        )));
+    }
+
+    #[test]
+    fn set_rejects_negative_and_fractional_numbers_after_a_valid_one() {
+        let mut view = test_view();
+
+        assert!(view.set("test", "precision", 2).is_ok());
+        assert!(is_type_mismatch(view.set("test", "precision", -1)));
+        assert!(is_type_mismatch(view.set("test", "precision", 1.5)));
+
+        let instance = view.instance::<TestData>("test").unwrap();
+        assert_eq!(instance.get().precision, 2);
+    }
+
+    #[test]
+    fn validate_hook_reports_unknown_components_and_properties() {
+        let view = test_view();
+
+        match view.validate_hook("other", "status", &serde_json::json!("Ok")) {
+            Err(HookError::UnknownComponent { component }) => assert_eq!(component, "other"),
+            _ => panic!("expected an unknown component"),
+        }
+
+        match view.validate_hook("test", "value", &serde_json::json!(1)) {
+            Err(HookError::UnknownProperty { property, .. }) => assert_eq!(property, "value"),
+            _ => panic!("expected an unknown property"),
+        }
+    }
+
+    #[test]
+    fn expected_types_come_from_the_schema() {
+        let mut view = test_view();
+        let targets = view.hook_targets();
+        let properties: HashMap<String, String> = targets[0].properties.iter().cloned().collect();
+
+        assert_eq!(properties["status"], "one of \"Ok\", \"Warning\", \"Error\"");
+        assert_eq!(properties["precision"], "a non-negative integer");
+        // not "null", which is just the current value
+        assert_eq!(properties["limit"], "a number or null");
+
+        match view.set("test", "limit", "high") {
+            Err(HookError::TypeMismatch { expected, .. }) => {
+                assert_eq!(expected, "a number or null")
+            }
+            _ => panic!("expected a type mismatch"),
+        }
+    }
+
+    #[test]
+    fn per_frame_hooks_are_checked_once() {
+        let mut view = test_view();
+        let mut hooks = Hooks::new();
+        add_hook(&mut hooks, "test", "precison", 2);
+        add_hook(&mut hooks, "test", "precision", 2);
+
+        view.check_new_hooks(&hooks);
+        view.check_new_hooks(&hooks);
+
+        let errors = view.drain_hook_errors();
+        assert_eq!(errors.len(), 1);
+        match &errors[0] {
+            HookError::UnknownProperty { property, .. } => assert_eq!(property, "precison"),
+            _ => panic!("expected an unknown property"),
+        }
+    }
+
+    #[test]
+    fn clear_restores_the_layout_data() {
+        let mut view = test_view();
+        let instance = view.instance::<TestData>("test").unwrap();
+
+        view.set("test", "precision", 3).unwrap();
+        assert_eq!(instance.get().precision, 3);
+
+        view.clear("test", "precision");
+        assert_eq!(instance.get().precision, 1);
+    }
+}
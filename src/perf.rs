@@ -0,0 +1,207 @@
+extern crate nanovg;
+
+use crate::frontend::dim_color;
+use nanovg::{Alignment, Color, Font, Frame, TextOptions};
+use std::time::Instant;
+
+const GRAPH_HISTORY_COUNT: usize = 100;
+const GRAPH_WIDTH: f32 = 200.0;
+const GRAPH_HEIGHT: f32 = 35.0;
+const OVERLAY_MARGIN: f32 = 5.0;
+
+fn clamp(value: f32, min: f32, max: f32) -> f32 {
+    value.max(min).min(max)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GraphRenderStyle {
+    Fps,     // values are frame times in seconds
+    Ms,      // values in seconds, shown in milliseconds
+    Percent, // values in 0..100
+}
+
+// Rolling graph of the last GRAPH_HISTORY_COUNT values, from the nanovg demo.
+pub struct PerformanceGraph {
+    style: GraphRenderStyle,
+    name: String,
+    values: [f32; GRAPH_HISTORY_COUNT],
+    head: usize,
+}
+
+impl PerformanceGraph {
+    pub fn new(style: GraphRenderStyle, name: &str) -> PerformanceGraph {
+        PerformanceGraph {
+            style: style,
+            name: String::from(name),
+            values: [0.0; GRAPH_HISTORY_COUNT],
+            head: 0,
+        }
+    }
+
+    pub fn update(&mut self, value: f32) {
+        self.head = (self.head + 1) % GRAPH_HISTORY_COUNT;
+        self.values[self.head] = value;
+    }
+
+    pub fn average(&self) -> f32 {
+        self.values.iter().sum::<f32>() / self.values.len() as f32
+    }
+
+    pub fn draw(&self, frame: &Frame, font: Font, x: f32, y: f32) {
+        self.draw_dimmed(frame, font, x, y, 1.0)
+    }
+
+    // colors multiplied by the brightness, see Palette::brightness
+    pub fn draw_dimmed(&self, frame: &Frame, font: Font, x: f32, y: f32, brightness: f32) {
+        let color =
+            |r: u8, g: u8, b: u8, a: u8| dim_color(Color::from_rgba(r, g, b, a), brightness);
+        let w = GRAPH_WIDTH;
+        let h = GRAPH_HEIGHT;
+        let average = self.average();
+
+        frame.path(
+            |path| {
+                path.rect((x, y), (w, h));
+                path.fill(color(0, 0, 0, 128), Default::default());
+            },
+            Default::default(),
+        );
+
+        // displayed units per value and the value at the top of the graph
+        let (scale, full_scale) = match self.style {
+            GraphRenderStyle::Fps => (1.0, 80.0),
+            GraphRenderStyle::Ms => (1000.0, 20.0),
+            GraphRenderStyle::Percent => (1.0, 100.0),
+        };
+
+        frame.path(
+            |path| {
+                path.move_to((x, y + h));
+                for i in 0..self.values.len() {
+                    let v = self.values[(self.head + 1 + i) % self.values.len()];
+                    let v = match self.style {
+                        GraphRenderStyle::Fps => 1.0 / (0.00001 + v),
+                        _ => v * scale,
+                    };
+                    let v = clamp(v, 0.0, full_scale);
+                    let vx = x + (i as f32 / (self.values.len() - 1) as f32) * w;
+                    let vy = y + h - ((v / full_scale) * h);
+                    path.line_to((vx, vy));
+                }
+
+                path.line_to((x + w, y + h));
+
+                path.fill(color(255, 192, 0, 128), Default::default());
+            },
+            Default::default(),
+        );
+
+        frame.text(
+            font,
+            (x + 3.0, y + 1.0),
+            &self.name,
+            TextOptions {
+                color: color(240, 240, 240, 192),
+                align: Alignment::new().left().top(),
+                size: 14.0,
+                ..Default::default()
+            },
+        );
+
+        let headline = match self.style {
+            GraphRenderStyle::Fps => format!("{:.2} FPS", 1.0 / average),
+            GraphRenderStyle::Ms => format!("{:.2} ms", average * 1000.0),
+            GraphRenderStyle::Percent => format!("{:.1} %", average),
+        };
+
+        frame.text(
+            font,
+            (x + w - 3.0, y + 1.0),
+            headline,
+            TextOptions {
+                size: 18.0,
+                color: color(240, 240, 240, 255),
+                align: Alignment::new().right().top(),
+                ..Default::default()
+            },
+        );
+
+        match self.style {
+            GraphRenderStyle::Fps => {
+                frame.text(
+                    font,
+                    (x + w - 3.0, y + h - 1.0),
+                    format!("{:.2} ms", average * 1000.0),
+                    TextOptions {
+                        size: 15.0,
+                        color: color(240, 240, 240, 160),
+                        align: Alignment::new().right().bottom(),
+                        ..Default::default()
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+// Frame time, CPU time spent drawing and number of hooked components of one screen.
+// Recorded all the time, so the history is there as soon as the overlay is shown.
+pub(crate) struct PerfOverlay {
+    pub(crate) visible: bool,
+    frame_time: PerformanceGraph,
+    cpu_time: PerformanceGraph,
+    hooked_components: usize,
+    last_frame: Option<Instant>,
+}
+
+impl PerfOverlay {
+    pub(crate) fn new() -> PerfOverlay {
+        PerfOverlay {
+            visible: false,
+            frame_time: PerformanceGraph::new(GraphRenderStyle::Fps, "Frame Time"),
+            cpu_time: PerformanceGraph::new(GraphRenderStyle::Ms, "CPU Time"),
+            hooked_components: 0,
+            last_frame: None,
+        }
+    }
+
+    // cpu_time: seconds spent building the frame, without waiting for the swap
+    pub(crate) fn record(&mut self, cpu_time: f32, hooked_components: usize) {
+        let now = Instant::now();
+
+        match self.last_frame {
+            Some(last_frame) => {
+                let elapsed = now - last_frame;
+                self.frame_time
+                    .update(elapsed.as_secs() as f32 + elapsed.subsec_nanos() as f32 * 1e-9);
+            }
+            None => {}
+        }
+
+        self.last_frame = Some(now);
+        self.cpu_time.update(cpu_time);
+        self.hooked_components = hooked_components;
+    }
+
+    pub(crate) fn draw(&self, frame: &Frame, font: Font, brightness: f32) {
+        let x = OVERLAY_MARGIN;
+        let y = OVERLAY_MARGIN;
+
+        self.frame_time.draw_dimmed(frame, font, x, y, brightness);
+        self.cpu_time
+            .draw_dimmed(frame, font, x + GRAPH_WIDTH + OVERLAY_MARGIN, y, brightness);
+
+        frame.text(
+            font,
+            (x + 3.0, y + GRAPH_HEIGHT + OVERLAY_MARGIN),
+            format!("{} hooked components", self.hooked_components),
+            TextOptions {
+                size: 14.0,
+                color: dim_color(Color::from_rgba(240, 240, 240, 192), brightness),
+                align: Alignment::new().left().top(),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,711 @@
+use crate::session;
+
+extern crate image;
+extern crate nanovg;
+extern crate nsvg;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem::{self, ManuallyDrop};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub enum ResourceError {
+    NotFound {
+        file: String,
+        searched: Vec<PathBuf>,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Decode {
+        path: PathBuf,
+        message: String,
+    },
+    UnknownFont(String),
+    UnknownImage(String),
+    Context(String), // no GL or nanovg context to load the resources into
+}
+
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResourceError::NotFound { file, searched } => write!(
+                f,
+                "{} not found in {}",
+                file,
+                searched
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ResourceError::Io { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            ResourceError::Decode { path, message } => {
+                write!(f, "cannot decode {}: {}", path.display(), message)
+            }
+            ResourceError::UnknownFont(name) => write!(f, "no font named \"{}\"", name),
+            ResourceError::UnknownImage(name) => write!(f, "no image named \"{}\"", name),
+            ResourceError::Context(message) => write!(f, "cannot create the context: {}", message),
+        }
+    }
+}
+
+impl error::Error for ResourceError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            ResourceError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// resources a component refers to from its data, checked when the layout is loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceRef {
+    Font(String),
+    Image(String),
+}
+
+pub const REGULAR_WEIGHT: u16 = 400;
+pub const BOLD_WEIGHT: u16 = 700;
+
+fn regular_weight() -> u16 {
+    REGULAR_WEIGHT
+}
+
+// Font of a text element in component data, i.e. {"family": "RobotoMono", "weight": 700}.
+// The face of the family closest to the weight is used (CSS-like, 400 regular, 700 bold).
+#[derive(serde::Serialize, serde::Deserialize, schemars::JsonSchema, Clone, Debug, PartialEq)]
+pub struct FontSelection {
+    pub family: String,
+    #[serde(default = "regular_weight")]
+    pub weight: u16,
+}
+
+impl FontSelection {
+    pub fn new(family: &str, weight: u16) -> FontSelection {
+        FontSelection {
+            family: family.to_string(),
+            weight: weight,
+        }
+    }
+}
+
+// families used by a component, for Component::resources
+pub fn font_refs(selections: &[&Option<FontSelection>]) -> Vec<ResourceRef> {
+    selections
+        .iter()
+        .filter_map(|selection| selection.as_ref())
+        .map(|selection| ResourceRef::Font(selection.family.clone()))
+        .collect()
+}
+
+// face of the family closest to the weight
+fn nearest_face(faces: &[(u16, String)], weight: u16) -> Option<&String> {
+    faces
+        .iter()
+        .min_by_key(|(face_weight, _)| (*face_weight as i32 - weight as i32).abs())
+        .map(|(_, name)| name)
+}
+
+// RGBA, one u32 per pixel in memory order (as nanovg takes it)
+pub(crate) struct ImageData {
+    pub(crate) path: PathBuf,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) pixels: Vec<u32>,
+}
+
+impl ImageData {
+    // every channel multiplied by the tint's
+    fn tinted(&self, tint: [u8; 4]) -> Vec<u32> {
+        self.pixels
+            .iter()
+            .map(|pixel| {
+                let mut channels = pixel.to_ne_bytes();
+                for (channel, factor) in channels.iter_mut().zip(tint.iter()) {
+                    *channel = ((*channel as u32 * *factor as u32) / 255) as u8;
+                }
+                u32::from_ne_bytes(channels)
+            })
+            .collect()
+    }
+}
+
+fn rgba_to_pixels(rgba: &[u8]) -> Vec<u32> {
+    rgba.chunks(4)
+        .map(|p| u32::from_ne_bytes([p[0], p[1], p[2], p[3]]))
+        .collect()
+}
+
+// Checks the sfnt header and that every table of the directory lies within the file, so
+// that nanovg won't reject the font once a screen loads it.
+fn check_font(path: &Path) -> Result<(), ResourceError> {
+    let data = fs::read(path).map_err(|er| ResourceError::Io {
+        path: path.to_path_buf(),
+        source: er,
+    })?;
+
+    check_font_data(&data).map_err(|message| ResourceError::Decode {
+        path: path.to_path_buf(),
+        message: message.to_string(),
+    })
+}
+
+fn check_font_data(data: &[u8]) -> Result<(), &'static str> {
+    let u16_at = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]) as usize)
+    };
+    let u32_at = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+
+    // a collection starts with the offsets of its fonts, the first one is used
+    let start = match data.get(0..4) {
+        Some(tag) if tag == b"ttcf" => u32_at(12).ok_or("truncated font collection")?,
+        _ => 0,
+    };
+
+    match data.get(start..start + 4) {
+        Some(tag) if tag == b"\x00\x01\x00\x00" || tag == b"true" || tag == b"OTTO" => {}
+        _ => return Err("not a TrueType or OpenType font"),
+    }
+
+    let tables = u16_at(start + 4).ok_or("truncated font header")?;
+
+    for i in 0..tables {
+        let record = start + 12 + i * 16;
+
+        match (u32_at(record + 8), u32_at(record + 12)) {
+            (Some(offset), Some(length)) if offset.saturating_add(length) <= data.len() => {}
+            _ => return Err("truncated font tables"),
+        }
+    }
+
+    Ok(())
+}
+
+// Named fonts and images, shared by all screens of a session. Files are looked up in the
+// search paths in the order they were added and checked when registered (images are
+// decoded, fonts validated), so a missing or broken file is reported right away.
+pub struct ResourceRegistry {
+    search_paths: Vec<PathBuf>,
+    fonts: HashMap<String, PathBuf>,
+    families: HashMap<String, Vec<(u16, String)>>, // (weight, font name) of each face
+    fallbacks: Vec<(String, String)>,              // (family, fallback family) in order
+    images: HashMap<String, Rc<ImageData>>,
+    default_font: Option<String>,
+}
+
+impl ResourceRegistry {
+    // searches "resources" relative to the working directory
+    pub fn new() -> ResourceRegistry {
+        ResourceRegistry {
+            search_paths: vec![PathBuf::from("resources")],
+            fonts: HashMap::new(),
+            families: HashMap::new(),
+            fallbacks: Vec::new(),
+            images: HashMap::new(),
+            default_font: None,
+        }
+    }
+
+    pub fn add_search_path<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.search_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn clear_search_paths(&mut self) -> &mut Self {
+        self.search_paths.clear();
+        self
+    }
+
+    fn resolve(&self, file: &str) -> Result<PathBuf, ResourceError> {
+        let path = Path::new(file);
+
+        if path.is_absolute() {
+            return match path.is_file() {
+                true => Ok(path.to_path_buf()),
+                false => Err(ResourceError::NotFound {
+                    file: file.to_string(),
+                    searched: Vec::new(),
+                }),
+            };
+        }
+
+        self.search_paths
+            .iter()
+            .map(|dir| dir.join(path))
+            .find(|candidate| candidate.is_file())
+            .ok_or_else(|| ResourceError::NotFound {
+                file: file.to_string(),
+                searched: self.search_paths.clone(),
+            })
+    }
+
+    // TrueType font, also selectable as a family of one regular face. The first font
+    // registered becomes the default font.
+    pub fn load_font(&mut self, name: &str, file: &str) -> Result<&mut Self, ResourceError> {
+        self.add_face(name, REGULAR_WEIGHT, name, file)
+    }
+
+    // face of a family, named "<family>-<weight>" (i.e. "Roboto-700")
+    pub fn load_font_face(
+        &mut self,
+        family: &str,
+        weight: u16,
+        file: &str,
+    ) -> Result<&mut Self, ResourceError> {
+        self.add_face(family, weight, &format!("{}-{}", family, weight), file)
+    }
+
+    fn add_face(
+        &mut self,
+        family: &str,
+        weight: u16,
+        name: &str,
+        file: &str,
+    ) -> Result<&mut Self, ResourceError> {
+        let path = self.resolve(file)?;
+        check_font(&path)?;
+
+        self.fonts.insert(name.to_string(), path);
+
+        let faces = self.families.entry(family.to_string()).or_insert_with(Vec::new);
+        faces.retain(|(face_weight, _)| *face_weight != weight);
+        faces.push((weight, name.to_string()));
+
+        match self.default_font {
+            Some(_) => {}
+            None => self.default_font = Some(name.to_string()),
+        }
+
+        Ok(self)
+    }
+
+    // Glyphs missing in the family are taken from the fallback family (i.e. CJK captions),
+    // fallbacks are tried in the order they were added.
+    pub fn add_fallback(
+        &mut self,
+        family: &str,
+        fallback: &str,
+    ) -> Result<&mut Self, ResourceError> {
+        for name in &[family, fallback] {
+            match self.families.contains_key(*name) {
+                true => {}
+                false => return Err(ResourceError::UnknownFont(name.to_string())),
+            }
+        }
+
+        self.fallbacks.push((family.to_string(), fallback.to_string()));
+
+        Ok(self)
+    }
+
+    pub fn set_default_font(&mut self, name: &str) -> Result<&mut Self, ResourceError> {
+        match self.fonts.contains_key(name) {
+            true => {
+                self.default_font = Some(name.to_string());
+                Ok(self)
+            }
+            false => Err(ResourceError::UnknownFont(name.to_string())),
+        }
+    }
+
+    // PNG or JPEG, SVG is rasterized at its own size
+    pub fn load_image(&mut self, name: &str, file: &str) -> Result<&mut Self, ResourceError> {
+        match Path::new(file).extension().and_then(|e| e.to_str()) {
+            Some("svg") | Some("SVG") => return self.load_svg(name, file, 1.0),
+            _ => {}
+        }
+
+        let path = self.resolve(file)?;
+        let rgba = match image::open(&path) {
+            Ok(image) => image.to_rgba(),
+            Err(image::ImageError::IoError(er)) => {
+                return Err(ResourceError::Io {
+                    path: path,
+                    source: er,
+                })
+            }
+            Err(er) => {
+                return Err(ResourceError::Decode {
+                    path: path,
+                    message: er.to_string(),
+                })
+            }
+        };
+        let (width, height) = rgba.dimensions();
+
+        self.images.insert(
+            name.to_string(),
+            Rc::new(ImageData {
+                path: path,
+                width: width,
+                height: height,
+                pixels: rgba_to_pixels(&rgba.into_raw()),
+            }),
+        );
+
+        Ok(self)
+    }
+
+    // scale 1.0 rasterizes the SVG at its own size in pixels
+    pub fn load_svg(
+        &mut self,
+        name: &str,
+        file: &str,
+        scale: f32,
+    ) -> Result<&mut Self, ResourceError> {
+        let path = self.resolve(file)?;
+
+        let decode_error = |er: nsvg::Error| ResourceError::Decode {
+            path: path.clone(),
+            message: format!("{:?}", er),
+        };
+
+        let svg = nsvg::parse_file(&path, nsvg::Units::Pixel, 96.0).map_err(decode_error)?;
+        let (width, height, rgba) = svg.rasterize_to_raw_rgba(scale).map_err(decode_error)?;
+
+        self.images.insert(
+            name.to_string(),
+            Rc::new(ImageData {
+                path: path,
+                width: width,
+                height: height,
+                pixels: rgba_to_pixels(&rgba),
+            }),
+        );
+
+        Ok(self)
+    }
+
+    // font or family
+    pub fn has_font(&self, name: &str) -> bool {
+        self.fonts.contains_key(name) || self.families.contains_key(name)
+    }
+
+    pub fn has_image(&self, name: &str) -> bool {
+        self.images.contains_key(name)
+    }
+
+    pub(crate) fn has_default_font(&self) -> bool {
+        self.default_font.is_some()
+    }
+}
+
+// A nanovg context with the fonts and images of a registry loaded into it. nanovg objects
+// can't be shared between GL contexts, so every screen has its own.
+pub struct ContextResources {
+    bound: BoundContext,
+    families: HashMap<String, Vec<(u16, String)>>,
+    default_font: String,
+}
+
+const MAX_TINTED_IMAGES: usize = 64;
+
+// Images stored together with the nanovg context they were created against. They are only
+// ever created by build() against the boxed context, which is never moved or handed out by
+// value, handed out no longer than a borrow of self and deleted before the context in Drop,
+// so the 'static lifetime they are stored with is never observable. Tinted copies are marked
+// when used in a frame and only deleted between frames, as nanovg draws at the end of a frame.
+struct BoundContext {
+    context: ManuallyDrop<Box<nanovg::Context>>,
+    images: HashMap<String, (Rc<ImageData>, nanovg::Image<'static>)>,
+    tinted: RefCell<HashMap<(String, [u8; 4]), (nanovg::Image<'static>, bool)>>,
+}
+
+impl BoundContext {
+    fn new(context: nanovg::Context) -> BoundContext {
+        BoundContext {
+            context: ManuallyDrop::new(Box::new(context)),
+            images: HashMap::new(),
+            tinted: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn context(&self) -> &nanovg::Context {
+        &self.context
+    }
+
+    fn build(
+        &self,
+        data: &ImageData,
+        pixels: &[u32],
+    ) -> Result<nanovg::Image<'static>, ResourceError> {
+        let image = build_image(self.context(), data, pixels)?;
+        Ok(unsafe { mem::transmute::<nanovg::Image, nanovg::Image<'static>>(image) })
+    }
+
+    fn insert(&mut self, name: &str, data: &Rc<ImageData>) -> Result<(), ResourceError> {
+        let image = self.build(data, &data.pixels)?;
+        self.images.insert(name.to_string(), (Rc::clone(data), image));
+        Ok(())
+    }
+
+    fn image(&self, name: &str) -> Option<(&ImageData, &nanovg::Image)> {
+        self.images.get(name).map(|(data, image)| (&**data, image))
+    }
+
+    fn with_tinted<R, F>(&self, name: &str, tint: [u8; 4], f: F) -> Result<R, ResourceError>
+    where
+        F: FnOnce(&nanovg::Image) -> R,
+    {
+        let (data, _) = self
+            .images
+            .get(name)
+            .ok_or_else(|| ResourceError::UnknownImage(name.to_string()))?;
+
+        let mut tinted = self.tinted.borrow_mut();
+        let key = (name.to_string(), tint);
+
+        if !tinted.contains_key(&key) {
+            let image = self.build(data, &data.tinted(tint))?;
+            tinted.insert(key.clone(), (image, false));
+        }
+
+        let entry = tinted.get_mut(&key).unwrap();
+        entry.1 = true;
+
+        Ok(f(&entry.0))
+    }
+
+    // animated tints would otherwise grow the cache without bounds, the copies used in the
+    // last frame are kept
+    fn end_frame(&self) {
+        let mut tinted = self.tinted.borrow_mut();
+
+        if tinted.len() > MAX_TINTED_IMAGES {
+            tinted.retain(|_, (_, used)| *used);
+        }
+
+        for (_, used) in tinted.values_mut() {
+            *used = false;
+        }
+    }
+}
+
+impl Drop for BoundContext {
+    fn drop(&mut self) {
+        self.tinted.get_mut().clear();
+        self.images.clear();
+
+        unsafe { ManuallyDrop::drop(&mut self.context) }
+    }
+}
+
+fn build_image<'a>(
+    context: &'a nanovg::Context,
+    data: &ImageData,
+    pixels: &[u32],
+) -> Result<nanovg::Image<'a>, ResourceError> {
+    nanovg::Image::new(context)
+        .build_from_rgba(data.width as usize, data.height as usize, pixels)
+        .map_err(|_| ResourceError::Decode {
+            path: data.path.clone(),
+            message: "cannot create a texture".to_string(),
+        })
+}
+
+fn color_to_rgba(color: nanovg::Color) -> [u8; 4] {
+    let channel = |v: f32| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+    [
+        channel(color.red()),
+        channel(color.green()),
+        channel(color.blue()),
+        channel(color.alpha()),
+    ]
+}
+
+impl ContextResources {
+    // expects the target GL context to be current
+    pub(crate) fn new(registry: &ResourceRegistry) -> Result<ContextResources, ResourceError> {
+        let mut bound = BoundContext::new(session::make_context()?);
+        let context = bound.context();
+
+        for (name, path) in &registry.fonts {
+            let file = path.to_string_lossy();
+
+            match nanovg::Font::from_file(context, name, &*file) {
+                Ok(_) => {}
+                Err(_) => {
+                    return Err(ResourceError::Decode {
+                        path: path.clone(),
+                        message: "not a TrueType font".to_string(),
+                    })
+                }
+            }
+        }
+
+        for (family, fallback) in &registry.fallbacks {
+            for (weight, name) in &registry.families[family] {
+                let fallback = nearest_face(&registry.families[fallback], *weight)
+                    .and_then(|fallback| nanovg::Font::find(context, fallback).ok());
+
+                match (nanovg::Font::find(context, name), fallback) {
+                    (Ok(font), Some(fallback)) => {
+                        font.add_fallback(fallback);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for (name, data) in &registry.images {
+            bound.insert(name, data)?;
+        }
+
+        Ok(ContextResources {
+            bound: bound,
+            families: registry.families.clone(),
+            default_font: registry.default_font.clone().unwrap_or_default(),
+        })
+    }
+
+    pub fn context(&self) -> &nanovg::Context {
+        self.bound.context()
+    }
+
+    pub fn font(&self, name: &str) -> Result<nanovg::Font, ResourceError> {
+        nanovg::Font::find(self.context(), name)
+            .map_err(|_| ResourceError::UnknownFont(name.to_string()))
+    }
+
+    pub fn select_font(&self, selection: &FontSelection) -> Result<nanovg::Font, ResourceError> {
+        let faces = self
+            .families
+            .get(&selection.family)
+            .ok_or_else(|| ResourceError::UnknownFont(selection.family.clone()))?;
+
+        match nearest_face(faces, selection.weight) {
+            Some(name) => self.font(name),
+            None => Err(ResourceError::UnknownFont(selection.family.clone())),
+        }
+    }
+
+    pub fn default_font(&self) -> nanovg::Font {
+        self.font(&self.default_font)
+            .expect("Default font missing in the nanovg context")
+    }
+
+    pub fn image(&self, name: &str) -> Result<&nanovg::Image, ResourceError> {
+        self.bound
+            .image(name)
+            .map(|(_, image)| image)
+            .ok_or_else(|| ResourceError::UnknownImage(name.to_string()))
+    }
+
+    pub fn image_size(&self, name: &str) -> Result<(u32, u32), ResourceError> {
+        self.bound
+            .image(name)
+            .map(|(data, _)| (data.width, data.height))
+            .ok_or_else(|| ResourceError::UnknownImage(name.to_string()))
+    }
+
+    // Calls f with the image multiplied by the tint (i.e. a white icon drawn in a status
+    // color). A tinted copy is made the first time a color is used and kept while in use.
+    pub fn with_tinted_image<R, F>(
+        &self,
+        name: &str,
+        tint: nanovg::Color,
+        f: F,
+    ) -> Result<R, ResourceError>
+    where
+        F: FnOnce(&nanovg::Image) -> R,
+    {
+        let tint = color_to_rgba(tint);
+
+        match tint == [255, 255, 255, 255] {
+            true => self.image(name).map(f),
+            false => self.bound.with_tinted(name, tint, f),
+        }
+    }
+
+    // called after every frame, deletes tinted copies that are no longer used
+    pub(crate) fn end_frame(&self) {
+        self.bound.end_frame();
+    }
+
+    pub fn check(&self, resource: &ResourceRef) -> Result<(), ResourceError> {
+        match resource {
+            ResourceRef::Font(name)
+                if !self.families.contains_key(name) && self.font(name).is_err() =>
+            {
+                Err(ResourceError::UnknownFont(name.clone()))
+            }
+            ResourceRef::Image(name) if self.image(name).is_err() => {
+                Err(ResourceError::UnknownImage(name.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTO: &str = "resources/Roboto-Regular.ttf";
+
+    #[test]
+    fn check_font_accepts_a_font() {
+        assert!(check_font(Path::new(ROBOTO)).is_ok());
+    }
+
+    #[test]
+    fn check_font_rejects_truncated_fonts() {
+        let data = fs::read(ROBOTO).unwrap();
+
+        assert_eq!(
+            check_font_data(&data[..data.len() / 2]),
+            Err("truncated font tables")
+        );
+        assert_eq!(check_font_data(&data[..5]), Err("truncated font header"));
+        assert_eq!(
+            check_font_data(b"ttcf\x00\x01\x00\x00"),
+            Err("truncated font collection")
+        );
+    }
+
+    #[test]
+    fn check_font_rejects_other_files() {
+        assert_eq!(
+            check_font_data(b"\x89PNG\r\n\x1a\n"),
+            Err("not a TrueType or OpenType font")
+        );
+        assert_eq!(check_font_data(b""), Err("not a TrueType or OpenType font"));
+
+        match ResourceRegistry::new().load_font("arch", "arch.png") {
+            Err(ResourceError::Decode { path, .. }) => assert!(path.ends_with("arch.png")),
+            _ => panic!("expected a decode error"),
+        }
+    }
+
+    #[test]
+    fn nearest_face_picks_the_closest_weight() {
+        let faces = vec![
+            (400, "Regular".to_string()),
+            (700, "Bold".to_string()),
+            (300, "Light".to_string()),
+        ];
+        let nearest = |weight: u16| nearest_face(&faces, weight).map(|name| name.as_str());
+
+        assert_eq!(nearest(400), Some("Regular"));
+        assert_eq!(nearest(500), Some("Regular"));
+        assert_eq!(nearest(650), Some("Bold"));
+        assert_eq!(nearest(900), Some("Bold"));
+        assert_eq!(nearest(100), Some("Light"));
+        assert_eq!(nearest_face(&[], 400), None);
+    }
+}
@@ -1,4 +1,8 @@
 use crate::frontend;
+use crate::perf::PerfOverlay;
+use crate::resources::{ContextResources, ResourceError, ResourceRegistry};
+use crate::watch::WatchedView;
+use crate::window::WindowConfig;
 use crate::*;
 
 extern crate gl;
@@ -9,212 +13,668 @@ extern crate rand;
 
 use glutin::GlContext;
 use nalgebra::Vector2;
-use std::time::Instant;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub(crate) fn make_context() -> Result<nanovg::Context, ResourceError> {
+    nanovg::ContextBuilder::new()
+        .stencil_strokes()
+        .build()
+        .map_err(|_| ResourceError::Context("initialization of NanoVG failed".to_string()))
+}
+
+// with the font used when none was registered
+pub(crate) fn default_resources(
+    mut registry: ResourceRegistry,
+) -> Result<ResourceRegistry, ResourceError> {
+    match registry.has_default_font() {
+        true => {}
+        false => {
+            registry.load_font("Roboto-Regular", "Roboto-Regular.ttf")?;
+        }
+    }
+
+    Ok(registry)
+}
+
+// shared by windowed and headless sessions, expects the target GL context to be current
+pub(crate) fn render_view(
+    resources: &ContextResources,
+    size: (u32, u32),
+    aspect: Option<f32>,
+    dpi: f32,
+    time: f32,
+    view: &mut View,
+    palette: &dyn frontend::Palette,
+    hooks: &Hooks,
+    overlay: Option<&str>,
+) {
+    let background = palette.background_color();
+
+    unsafe {
+        gl::Viewport(0, 0, size.0 as i32, size.1 as i32);
+        gl::ClearColor(background.red(), background.green(), background.blue(), 1.0);
+        gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+    }
+
+    let (width, height) = (size.0 as f32, size.1 as f32);
+
+    resources.context().frame((width, height), dpi, |frame| {
+        let res = frontend::Resources {
+            palette: palette,
+            font: resources.default_font(),
+            loaded: resources,
+        };
+
+        let mut ctx = frontend::PresentationContext {
+            frame: frame,
+            time: time,
+            resources: res,
+        };
+
+        let zone = DrawZone::from_rect(Vector2::new(0.0, 0.0), Vector2::new(width, height));
+
+        view.draw(&mut ctx, zone.constraint_to_aspect(aspect), hooks);
+
+        match overlay {
+            Some(text) => draw_error_overlay(&mut ctx, zone, text),
+            None => {}
+        }
+    });
+
+    resources.end_frame();
+}
+
+fn draw_error_overlay(ctx: &mut frontend::PresentationContext, zone: DrawZone, text: &str) {
+    let margin = 10.0;
+    let palette = ctx.resources.palette;
+
+    let text_opts = nanovg::TextOptions {
+        color: palette.status_to_color_font(frontend::Status::Error),
+        size: 16.0,
+        align: nanovg::Alignment::new().left().top(),
+        line_max_width: zone.size.x - 2.0 * margin,
+        ..Default::default()
+    };
+
+    let bounds = ctx.frame.text_box_bounds(
+        ctx.resources.font,
+        (zone.left() + margin, zone.bottom() + margin),
+        text,
+        text_opts,
+    );
+
+    let bg = palette.status_to_color_bg(frontend::Status::Error);
+
+    ctx.frame.path(
+        |mut path| {
+            path.rect(
+                (zone.left(), zone.bottom()),
+                (zone.size.x, bounds.max_y - zone.bottom() + margin),
+            );
+            path.fill(
+                nanovg::Color::new(bg.red(), bg.green(), bg.blue(), 0.85),
+                Default::default(),
+            );
+        },
+        Default::default(),
+    );
+
+    ctx.frame.text_box(
+        ctx.resources.font,
+        (zone.left() + margin, zone.bottom() + margin),
+        text,
+        text_opts,
+    );
+}
+
+pub(crate) fn load_view(
+    resources: &ContextResources,
+    manager: &Manager,
+    size: (u32, u32),
+    dpi: f32,
+    path_to_json: &str,
+) -> Result<View, LayoutError> {
+    let mut ret = None; //fixme
+
+    resources.context().frame((size.0 as f32, size.1 as f32), dpi, |frame| {
+        let res = frontend::Resources {
+            palette: &frontend::DarkPalette {},
+            font: resources.default_font(),
+            loaded: resources,
+        };
+
+        let mut ctx = frontend::PresentationContext {
+            frame: frame,
+            time: 0.0,
+            resources: res,
+        };
+
+        ret = Some(manager.make_screen(&mut ctx, path_to_json))
+    });
+
+    ret.unwrap()
+}
+
+fn as_secs(duration: Duration) -> f32 {
+    (duration.as_secs() as f64 + duration.subsec_nanos() as f64 * 1e-9) as f32
+}
+
+pub(crate) fn get_elapsed_time(instant: &Instant) -> f32 {
+    as_secs(instant.elapsed())
+}
+
+// collected by Session::run
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    pub frames: u32,
+    pub elapsed: Duration,
+    // spent in the update closure, without the limiter's sleep and the time blocked in
+    // swap_buffers, which waits for the display under vsync
+    pub busy: Duration,
+    pub min_frame_time: Duration,
+    pub max_frame_time: Duration,
+}
+
+impl FrameStats {
+    fn new() -> FrameStats {
+        FrameStats {
+            frames: 0,
+            elapsed: Duration::new(0, 0),
+            busy: Duration::new(0, 0),
+            min_frame_time: Duration::new(0, 0),
+            max_frame_time: Duration::new(0, 0),
+        }
+    }
+
+    fn record(&mut self, frame_time: Duration) {
+        if self.frames == 0 || frame_time < self.min_frame_time {
+            self.min_frame_time = frame_time;
+        }
+        if frame_time > self.max_frame_time {
+            self.max_frame_time = frame_time;
+        }
+        self.frames += 1;
+    }
 
-const INIT_WINDOW_SIZE: (u32, u32) = (800, 800);
+    pub fn average_fps(&self) -> f32 {
+        self.frames as f32 / as_secs(self.elapsed).max(std::f32::EPSILON)
+    }
 
+    // fraction of the time spent working, 1.0 means the target FPS isn't met
+    pub fn load(&self) -> f32 {
+        as_secs(self.busy) / as_secs(self.elapsed).max(std::f32::EPSILON)
+    }
+}
 
-fn get_elapsed_time(instant: &Instant) -> f32 {
-    let elapsed = instant.elapsed();
-    let elapsed = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 * 1e-9;
-    elapsed as f32
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} frames, {:.1} fps, frame time {:.1}-{:.1} ms, load {:.0}%",
+            self.frames,
+            self.average_fps(),
+            as_secs(self.min_frame_time) * 1000.0,
+            as_secs(self.max_frame_time) * 1000.0,
+            self.load() * 100.0
+        )
+    }
 }
 
+// returned by Session::set_target_fps for a rate that is not a positive, finite number
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InvalidTargetFps(pub f32);
+
+impl fmt::Display for InvalidTargetFps {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "target fps must be positive and finite, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTargetFps {}
+
 pub struct SessionBuilder {
-    manager: Manager,
+    pub(crate) manager: Manager,
+    pub(crate) resources: ResourceRegistry,
+    window: WindowConfig,
 }
 
-pub struct Session<'a> {
-    context: &'a nanovg::Context,
-    font: nanovg::Font<'a>,
+pub struct Session {
     manager: Manager,
-    default_screen: Screen,
-    start_time: Instant
+    resources: ResourceRegistry,
+    screens: Vec<Screen>,
+    events_loop: glutin::EventsLoop, // shared by all screens, events are routed by window id
+    start_time: Instant,
+    frame_start: Instant,
+    target_frame_time: Option<Duration>,
+    redraw_on_change: bool,
+    swap_time: Duration, // spent in swap_buffers, excluded from FrameStats::busy
 }
 
-pub struct Screen {
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScreenId(usize);
+
+// the window created by SessionBuilder::init
+pub const DEFAULT_SCREEN: ScreenId = ScreenId(0);
+
+struct Screen {
+    resources: ContextResources, // nanovg context of the window, dropped before it
     gl_window: glutin::GlWindow,
-    events_loop: glutin::EventsLoop
+    aspect: Option<f32>,
+    cursor: Vector2<f32>,
+    inputs: Vec<input::InputEvent>, // received since the screen was last drawn
+    closed: bool,
+    perf: PerfOverlay,
+    invalidated: bool, // the window contents got lost, i.e. resized
+    last_view: Option<usize>, // id of the view drawn last
+    last_palette_revision: Option<u64>,
+    last_palette_animated: bool, // the last frame was drawn mid-animation, i.e. of a cross-fade
+}
+
+impl Screen {
+    fn new(
+        events_loop: &glutin::EventsLoop,
+        config: &WindowConfig,
+        registry: &ResourceRegistry,
+    ) -> Result<Screen, ResourceError> {
+        let gl_window = config.build(events_loop);
+
+        Screen::make_current_window(&gl_window);
+
+        let resources = ContextResources::new(registry)?;
+
+        Ok(Screen {
+            resources: resources,
+            gl_window: gl_window,
+            aspect: config.aspect,
+            cursor: Vector2::new(0.0, 0.0),
+            inputs: Vec::new(),
+            closed: false,
+            perf: PerfOverlay::new(),
+            invalidated: true,
+            last_view: None,
+            last_palette_revision: None,
+            last_palette_animated: false,
+        })
+    }
+
+    fn make_current_window(gl_window: &glutin::GlWindow) {
+        unsafe {
+            gl_window.make_current().unwrap();
+            gl::load_with(|symbol| gl_window.get_proc_address(symbol) as *const _);
+        }
+    }
+
+    fn make_current(&self) {
+        Screen::make_current_window(&self.gl_window);
+    }
+
+    fn size(&self) -> (u32, u32) {
+        self.gl_window.get_inner_size().unwrap()
+    }
+
+    fn handle_window_event(&mut self, event: glutin::WindowEvent) {
+        match event {
+            glutin::WindowEvent::Closed => self.closed = true,
+            glutin::WindowEvent::Resized(w, h) => {
+                self.gl_window.resize(w, h);
+                self.invalidated = true;
+            }
+            glutin::WindowEvent::Refresh => self.invalidated = true,
+            event => match input::from_glutin(&event, &mut self.cursor) {
+                Some(input) => self.inputs.push(input),
+                None => {}
+            },
+        }
+    }
 }
 
-// multi-screen capability waits for glutin support for multi-window with one context
-pub enum __TargetScreen<'a> {
-    Custom(&'a mut Screen),
-    Default,
+impl Drop for Screen {
+    // nanovg deletes its GL objects in whatever context is current
+    fn drop(&mut self) {
+        self.make_current();
+    }
 }
 
 impl SessionBuilder {
     pub fn new() -> SessionBuilder {
         SessionBuilder {
             manager: Manager::new(),
+            resources: ResourceRegistry::new(),
+            window: WindowConfig::default(),
         }
     }
 
+    // fonts and images available to the layouts, see ResourceRegistry
+    pub fn resources(mut self, registry: ResourceRegistry) -> Self {
+        self.resources = registry;
+        self
+    }
+
+    // window of the default screen
+    pub fn window(mut self, config: WindowConfig) -> Self {
+        self.window = config;
+        self
+    }
+
     pub fn register_components<F: Fn(&mut Manager)>(mut self, components: F) -> Self {
         components(&mut self.manager);
         self
     }
 
-    fn make_screen() -> Screen {
+    // fails if the resources can't be loaded into the window's context
+    pub fn init<F: Fn(&mut Session)>(self, handler: F) -> Result<(), ResourceError> {
         let events_loop = glutin::EventsLoop::new();
-        let window = glutin::WindowBuilder::new()
-            .with_title("Gaugen Demo")
-            .with_dimensions(INIT_WINDOW_SIZE.0, INIT_WINDOW_SIZE.1);
-        let context = glutin::ContextBuilder::new()
-            .with_vsync(false)
-            .with_multisampling(4)
-            .with_srgb(true);
-        let gl_window = glutin::GlWindow::new(window, context, &events_loop).unwrap();
-
-        Screen {
-            gl_window: gl_window,
+        let resources = default_resources(self.resources)?;
+        let default_screen = Screen::new(&events_loop, &self.window, &resources)?;
+
+        let mut session = Session {
+            manager: self.manager,
+            resources: resources,
+            screens: vec![default_screen],
             events_loop: events_loop,
-        }
+            start_time: Instant::now(),
+            frame_start: Instant::now(),
+            target_frame_time: None,
+            redraw_on_change: false,
+            swap_time: Duration::new(0, 0),
+        };
+
+        handler(&mut session);
+
+        Ok(())
     }
+}
 
-    pub fn init<F: Fn(&mut Session)>(self, handler: F) {
-        let default_screen = SessionBuilder::make_screen();
+impl Session {
+    // Opens another window, i.e. on a second monitor. Views are bound to the screen they were
+    // made for (see new_view_on), the component registry is shared.
+    pub fn open_screen(&mut self, config: &WindowConfig) -> Result<ScreenId, ResourceError> {
+        let screen = Screen::new(&self.events_loop, config, &self.resources)?;
+        self.screens.push(screen);
+        Ok(ScreenId(self.screens.len() - 1))
+    }
 
-        unsafe {
-            default_screen.gl_window.make_current().unwrap();
-            gl::load_with(|symbol| default_screen.gl_window.get_proc_address(symbol) as *const _);
+    // None (the default) doesn't limit the frame rate, vsync can be enabled in WindowConfig.
+    // A rate that is not a positive, finite number is rejected and leaves the limit unchanged.
+    pub fn set_target_fps(&mut self, fps: Option<f32>) -> Result<(), InvalidTargetFps> {
+        self.target_frame_time = match fps {
+            Some(fps) if !(fps.is_finite() && fps > 0.0) => return Err(InvalidTargetFps(fps)),
+            Some(fps) => {
+                let period = 1.0 / fps as f64;
+                Some(Duration::new(period as u64, (period.fract() * 1e9) as u32))
+            }
+            None => None,
+        };
+
+        Ok(())
+    }
+
+    // For custom loops, called once all screens are drawn. Sleeps for the rest of the frame
+    // period and returns the duration of the frame that just ended.
+    pub fn end_frame(&mut self) -> Duration {
+        match self.target_frame_time {
+            Some(period) => {
+                let spent = self.frame_start.elapsed();
+                if spent < period {
+                    thread::sleep(period - spent);
+                }
+            }
+            None => {}
         }
 
-        let context = nanovg::ContextBuilder::new()
-            .stencil_strokes()
-            .build()
-            .expect("Initialization of NanoVG failed!");
+        let now = Instant::now();
+        let frame_time = now - self.frame_start;
+        self.frame_start = now;
 
-        let font =
-            nanovg::Font::from_file(&context, "Roboto-Regular", "resources/Roboto-Regular.ttf")
-                .expect("Failed to load font 'Roboto-Regular.ttf'");
+        frame_time
+    }
 
-        let mut session = Session {
-            context: &context,
-            font: font,
-            manager: self.manager,
-            default_screen: default_screen,
-            start_time: Instant::now()
-        };
+    // Owns the draw loop: calls update with the time since the previous frame (in seconds),
+    // which draws the screens and returns false to stop.
+    pub fn run<F>(&mut self, mut update: F) -> FrameStats
+    where
+        F: FnMut(&mut Session, f32) -> bool,
+    {
+        let mut stats = FrameStats::new();
+        let start = Instant::now();
+        let mut delta = 0.0;
+
+        self.frame_start = start;
+
+        loop {
+            let update_start = Instant::now();
+            let swap_time = self.swap_time;
+            let proceed = update(self, delta);
+            stats.busy += update_start.elapsed() - (self.swap_time - swap_time);
+
+            if !proceed {
+                break;
+            }
+
+            let frame_time = self.end_frame();
+            stats.record(frame_time);
+            delta = as_secs(frame_time);
+        }
 
-        handler(&mut session);
+        stats.elapsed = start.elapsed();
+        stats
+    }
+
+    // graphs of frame time and CPU draw time, plus the number of hooked components
+    pub fn set_perf_overlay(&mut self, screen: ScreenId, visible: bool) {
+        self.screens[screen.0].perf.visible = visible;
     }
-}
 
-impl Session<'_> {
-    pub fn draw(
+    pub fn toggle_perf_overlay(&mut self, screen: ScreenId) {
+        let perf = &mut self.screens[screen.0].perf;
+        perf.visible = !perf.visible;
+    }
+
+    // When enabled, draw skips frames in which nothing changed (see View::needs_redraw) and
+    // leaves the last presented frame on screen. Changes of a PaletteSwitch are picked up,
+    // drawing with a different palette needs View::invalidate.
+    pub fn set_redraw_on_change(&mut self, redraw_on_change: bool) {
+        self.redraw_on_change = redraw_on_change;
+    }
+
+    pub fn draw(&mut self, view: &mut View, palette: &dyn frontend::Palette, hooks: &Hooks) -> bool {
+        self.draw_on(DEFAULT_SCREEN, view, palette, hooks)
+    }
+
+    // returns false once the window of the screen has been closed
+    pub fn draw_on(
         &mut self,
-        //screen: TargetScreen,
+        screen: ScreenId,
         view: &mut View,
         palette: &dyn frontend::Palette,
         hooks: &Hooks,
     ) -> bool {
-        /*
-        see __TargetScreen
-        let screen = match screen {
-            TargetScreen::Custom(screen) => screen,
-            _ => &mut self.default_screen,
-        };
-        */
-        let screen = &mut self.default_screen;
+        self.draw_with_overlay(screen, view, palette, hooks, None)
+    }
 
-        unsafe {
-            screen.gl_window.make_current().unwrap();
-            gl::load_with(|symbol| screen.gl_window.get_proc_address(symbol) as *const _);
-        }
+    pub fn draw_watched(
+        &mut self,
+        watched: &mut WatchedView,
+        palette: &dyn frontend::Palette,
+        hooks: &Hooks,
+    ) -> bool {
+        self.draw_watched_on(DEFAULT_SCREEN, watched, palette, hooks)
+    }
 
-        let mut mx = 0.0f32;
-        let mut my = 0.0f32;
-        let mut quit = false;
+    // reloads the layout if its file changed, a failed reload is shown on top of the last good tree
+    pub fn draw_watched_on(
+        &mut self,
+        screen: ScreenId,
+        watched: &mut WatchedView,
+        palette: &dyn frontend::Palette,
+        hooks: &Hooks,
+    ) -> bool {
+        watched.reload_if_changed(|path| self.new_view_on(screen, path));
 
-        let __window = &mut screen.gl_window;
+        let overlay = match watched.error() {
+            Some(er) => Some(format!("{}: {}", watched.path(), er)),
+            None => None,
+        };
+
+        self.draw_with_overlay(
+            screen,
+            watched.view(),
+            palette,
+            hooks,
+            overlay.as_ref().map(|text| text.as_str()),
+        )
+    }
 
-        screen.events_loop.poll_events(|event| match event {
-            glutin::Event::WindowEvent { event, .. } => match event {
-                glutin::WindowEvent::Closed => quit = true,
-                glutin::WindowEvent::Resized(w, h) => __window.resize(w, h),
-                glutin::WindowEvent::CursorMoved { position, .. } => {
-                    mx = position.0 as f32;
-                    my = position.1 as f32;
+    // events of all windows are received together, each screen keeps its own until drawn
+    fn poll_events(&mut self) {
+        let screens = &mut self.screens;
+
+        self.events_loop.poll_events(|event| match event {
+            glutin::Event::WindowEvent { window_id, event } => {
+                match screens.iter_mut().find(|s| s.gl_window.id() == window_id) {
+                    Some(screen) => screen.handle_window_event(event),
+                    None => {}
                 }
-                _ => {}
-            },
+            }
             _ => {}
         });
+    }
 
-        if quit {
+    fn draw_with_overlay(
+        &mut self,
+        screen: ScreenId,
+        view: &mut View,
+        palette: &dyn frontend::Palette,
+        hooks: &Hooks,
+        overlay: Option<&str>,
+    ) -> bool {
+        self.poll_events();
+
+        // every screen has its own nanovg context, the view's fonts and images are bound to one
+        assert!(
+            view.screen() == Some(screen),
+            "View drawn on {:?}, but built for {:?}",
+            screen,
+            view.screen()
+        );
+
+        let time = get_elapsed_time(&self.start_time);
+        let screen = &mut self.screens[screen.0];
+
+        if screen.closed {
             return false;
         }
 
-        let (width, height) = screen.gl_window.get_inner_size().unwrap();
-        let (width, height) = (width as i32, height as i32);
+        // hit-tested against the zones of the previous frame, i.e. what the user saw
+        for input in std::mem::replace(&mut screen.inputs, Vec::new()) {
+            view.handle_event(&input);
+        }
 
-        unsafe {
-            gl::Viewport(0, 0, width, height);
-            gl::ClearColor(0.0, 0.0, 0.0, 1.0);
-            gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+        let redraw = !self.redraw_on_change
+            || screen.invalidated
+            || screen.perf.visible
+            || overlay.is_some()
+            || screen.last_view != Some(view.id())
+            || screen.last_palette_revision != Some(palette.revision())
+            || palette.is_animated()
+            || screen.last_palette_animated
+            || view.needs_redraw(hooks);
+
+        if !redraw {
+            return true;
         }
 
-        let (width, height, dpi) = (width as f32, height as f32, screen.gl_window.hidpi_factor());
+        screen.invalidated = false;
+        screen.last_view = Some(view.id());
+        screen.last_palette_revision = Some(palette.revision());
+        screen.last_palette_animated = palette.is_animated();
+
+        let draw_start = Instant::now();
+        let size = screen.size();
+        let dpi = screen.gl_window.hidpi_factor();
+
+        screen.make_current();
+
+        render_view(
+            &screen.resources,
+            size,
+            screen.aspect,
+            dpi,
+            time,
+            view,
+            palette,
+            hooks,
+            overlay,
+        );
 
-        let __font = self.font; //so no "self" is not used in closure
-        let __time = &self.start_time;
+        let cpu_time = as_secs(draw_start.elapsed());
 
-        self.context.frame((width, height), dpi, |mut frame| {
-            let res = frontend::Resources {
-                palette: palette,
-                font: __font,
-            };
+        // walks the tree, so only counted while shown
+        let hooked_components = match screen.perf.visible {
+            true => view.hooked_components(hooks),
+            false => 0,
+        };
 
-            let mut ctx = frontend::PresentationContext {
-                frame: frame,
-                time: get_elapsed_time(__time),
-                resources: res
-            };
+        screen.perf.record(cpu_time, hooked_components);
 
-            let zone =
-                DrawZone::from_rect(Vector2::new(0.0, 0.0), Vector2::new(width, height));
+        if screen.perf.visible {
+            let font = screen.resources.default_font();
+            let perf = &screen.perf;
+            let brightness = palette.brightness();
 
-            view.draw(&mut ctx, zone, hooks);
-        });
+            screen
+                .resources
+                .context()
+                .frame((size.0 as f32, size.1 as f32), dpi, |frame| {
+                    perf.draw(&frame, font, brightness)
+                });
+        }
 
+        let swap_start = Instant::now();
         screen.gl_window.swap_buffers().unwrap();
+        self.swap_time += swap_start.elapsed();
 
         true
     }
 
-    pub fn new_view(&self, path_to_json: &str) -> Option<View> {
-        let mut ret = None; //fixme
-
-        let (width, height) = self.default_screen.gl_window.get_inner_size().unwrap();
-
-        self.context.frame(
-            (width as f32, height as f32),
-            self.default_screen.gl_window.hidpi_factor(),
-            |mut frame| {
-                let res = frontend::Resources {
-                    palette: &frontend::DarkPalette {},
-                    font: self.font,
-                };
-
-                let mut ctx = frontend::PresentationContext {
-                    frame: frame,
-                    time: 0.0,
-                    resources: res
-                };
+    pub fn new_view(&self, path_to_json: &str) -> Result<View, LayoutError> {
+        self.new_view_on(DEFAULT_SCREEN, path_to_json)
+    }
 
-                ret = Some(self.manager.make_screen(
-                    &mut ctx,
-                    path_to_json,
-                ))
-            },
-        );
+    // Text is measured and fonts are found in the screen's context, so the view can only be
+    // drawn on that screen. Drawing it on another one panics.
+    pub fn new_view_on(
+        &self,
+        screen_id: ScreenId,
+        path_to_json: &str,
+    ) -> Result<View, LayoutError> {
+        let screen = &self.screens[screen_id.0];
+
+        screen.make_current();
+
+        let mut view = load_view(
+            &screen.resources,
+            &self.manager,
+            screen.size(),
+            screen.gl_window.hidpi_factor(),
+            path_to_json,
+        )?;
+
+        view.set_screen(screen_id);
+        Ok(view)
+    }
 
-        ret.unwrap()
+    pub fn new_watched_view(&self, path_to_json: &str) -> Result<WatchedView, LayoutError> {
+        self.new_watched_view_on(DEFAULT_SCREEN, path_to_json)
     }
 
-    //see __TargetSreen
-    fn __new_screen(&mut self) -> Screen {
-        SessionBuilder::make_screen()
+    pub fn new_watched_view_on(
+        &self,
+        screen: ScreenId,
+        path_to_json: &str,
+    ) -> Result<WatchedView, LayoutError> {
+        WatchedView::new(path_to_json, |path| self.new_view_on(screen, path))
     }
 }
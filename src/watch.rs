@@ -0,0 +1,81 @@
+use crate::*;
+
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// A view bound to its layout file. When the file changes, the tree is rebuilt and swapped in
+// only if the whole layout loads, otherwise the last good tree is kept along with the error.
+// The new tree takes over retained values, staleness timeouts and the data behind typed
+// instances of components that kept their name and type.
+pub struct WatchedView {
+    path: String,
+    modified: Option<SystemTime>,
+    last_poll: Instant,
+    view: View,
+    error: Option<LayoutError>,
+}
+
+fn modification_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+impl WatchedView {
+    pub(crate) fn new<F>(path_to_json: &str, load: F) -> Result<WatchedView, LayoutError>
+    where
+        F: FnOnce(&str) -> Result<View, LayoutError>,
+    {
+        let modified = modification_time(path_to_json);
+
+        Ok(WatchedView {
+            path: path_to_json.to_string(),
+            modified: modified,
+            last_poll: Instant::now(),
+            view: load(path_to_json)?,
+            error: None,
+        })
+    }
+
+    // returns true if a new tree has been swapped in
+    pub(crate) fn reload_if_changed<F>(&mut self, load: F) -> bool
+    where
+        F: FnOnce(&str) -> Result<View, LayoutError>,
+    {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let modified = modification_time(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+
+        match load(&self.path) {
+            Ok(view) => {
+                let previous = std::mem::replace(&mut self.view, view);
+                self.view.adopt(previous);
+                self.error = None;
+                true
+            }
+            Err(er) => {
+                self.error = Some(er);
+                false
+            }
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn view(&mut self) -> &mut View {
+        &mut self.view
+    }
+
+    // error of the last failed reload, cleared by the next successful one
+    pub fn error(&self) -> Option<&LayoutError> {
+        self.error.as_ref()
+    }
+}
@@ -0,0 +1,135 @@
+extern crate glutin;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Monitor {
+    Primary,
+    Index(usize), // in the order reported by the system
+    Name(String),
+}
+
+// Describes the window of a screen, i.e.
+// WindowConfig::new("PFD").fullscreen(Monitor::Index(1)).aspect(4.0 / 3.0)
+#[derive(Clone, Debug)]
+pub struct WindowConfig {
+    title: String,
+    size: (u32, u32),
+    fullscreen: Option<Monitor>,
+    decorations: bool,
+    resizable: bool,
+    pub(crate) aspect: Option<f32>,
+    vsync: bool,
+    multisampling: u16,
+    srgb: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> WindowConfig {
+        WindowConfig::new("Gaugen Demo")
+    }
+}
+
+impl WindowConfig {
+    pub fn new(title: &str) -> WindowConfig {
+        WindowConfig {
+            title: title.to_string(),
+            size: (800, 800),
+            fullscreen: None,
+            decorations: true,
+            resizable: true,
+            aspect: None,
+            vsync: false,
+            multisampling: 4,
+            srgb: true,
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = (width, height);
+        self
+    }
+
+    // borderless, at the resolution of the monitor; falls back to the primary monitor when
+    // the requested one isn't connected
+    pub fn fullscreen(mut self, monitor: Monitor) -> Self {
+        self.fullscreen = Some(monitor);
+        self
+    }
+
+    pub fn decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    // width / height the layout is kept at, the rest of the window stays background
+    pub fn aspect(mut self, aspect: f32) -> Self {
+        self.aspect = Some(aspect);
+        self
+    }
+
+    pub fn vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+
+    // samples per pixel, 0 disables multisampling
+    pub fn multisampling(mut self, samples: u16) -> Self {
+        self.multisampling = samples;
+        self
+    }
+
+    pub fn srgb(mut self, srgb: bool) -> Self {
+        self.srgb = srgb;
+        self
+    }
+
+    fn find_monitor(&self, events_loop: &glutin::EventsLoop) -> Option<glutin::MonitorId> {
+        let monitor = match self.fullscreen {
+            Some(ref monitor) => monitor,
+            None => return None,
+        };
+
+        let found = match monitor {
+            Monitor::Primary => None,
+            Monitor::Index(i) => events_loop.get_available_monitors().nth(*i),
+            Monitor::Name(name) => events_loop
+                .get_available_monitors()
+                .find(|m| m.get_name().as_ref() == Some(name)),
+        };
+
+        Some(found.unwrap_or_else(|| events_loop.get_primary_monitor()))
+    }
+
+    pub(crate) fn build(&self, events_loop: &glutin::EventsLoop) -> glutin::GlWindow {
+        let monitor = self.find_monitor(events_loop);
+
+        let size = match monitor {
+            Some(ref monitor) => monitor.get_dimensions(),
+            None => self.size,
+        };
+
+        let mut window = glutin::WindowBuilder::new()
+            .with_title(self.title.clone())
+            .with_dimensions(size.0, size.1)
+            .with_decorations(self.decorations && monitor.is_none())
+            .with_fullscreen(monitor);
+
+        // glutin has no resizable flag yet, pinning the size has the same effect
+        if !self.resizable {
+            window = window
+                .with_min_dimensions(size.0, size.1)
+                .with_max_dimensions(size.0, size.1);
+        }
+
+        let context = glutin::ContextBuilder::new()
+            .with_vsync(self.vsync)
+            .with_multisampling(self.multisampling)
+            .with_srgb(self.srgb);
+
+        glutin::GlWindow::new(window, context, events_loop).expect("Failed to create window")
+    }
+}
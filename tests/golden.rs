@@ -0,0 +1,76 @@
+extern crate gaugen;
+
+use std::env;
+use std::sync::Mutex;
+
+// Golden-image tests of the built-in components, rendered offscreen and compared against
+// the references in resources/golden. Run with GAUGEN_BLESS=1 to (re)generate them. They are
+// ignored by default, as the references depend on the GL stack they were rendered with:
+// cargo test --test golden -- --ignored
+
+static GL: Mutex<()> = Mutex::new(());
+
+fn run_golden(name: &str, layout: &str, frames: &str) {
+    // one headless GL context at a time
+    let __lock = GL.lock().unwrap_or_else(|er| er.into_inner());
+    let bless = env::var("GAUGEN_BLESS").is_ok();
+
+    let result = gaugen::session::SessionBuilder::new()
+        .register_components(gaugen::basic_components::components())
+        .register_components(gaugen::geometry_components::components())
+        .init_headless(400, 400, |session: &mut gaugen::headless::HeadlessSession| {
+            let test = gaugen::golden::GoldenTest::new(name, layout, "resources/golden")
+                .frames_from_file(frames)
+                .expect("Failed to load frames")
+                .update_references(bless);
+
+            match test.run(session, &gaugen::frontend::DarkPalette {}) {
+                Ok(()) => {}
+                Err(er) => panic!(
+                    "{}: {}\n(re)generate the references with GAUGEN_BLESS=1",
+                    name, er
+                ),
+            }
+        });
+
+    match result {
+        Ok(()) => {}
+        // i.e. neither OSMesa nor a GL 3.2 driver available
+        Err(gaugen::resources::ResourceError::Context(message)) => {
+            eprintln!("{}: skipped, no GL context ({})", name, message)
+        }
+        Err(er) => panic!("Failed to load the resources: {}", er),
+    }
+}
+
+// in range, warning, then out of range with the caption shown and blinked out
+#[test]
+#[ignore]
+fn rotational_indicator_blink() {
+    run_golden(
+        "blink",
+        "resources/golden/blink.json",
+        "resources/golden/blink_frames.json",
+    );
+}
+
+// level, pitched up, banked, a combined attitude and close to vertical
+#[test]
+#[ignore]
+fn spatial_situation_indicator_attitudes() {
+    run_golden(
+        "ssi",
+        "resources/golden/ssi.json",
+        "resources/golden/ssi_frames.json",
+    );
+}
+
+#[test]
+#[ignore]
+fn readme_layout() {
+    run_golden(
+        "readme",
+        "resources/screen_readme.json",
+        "resources/golden/readme_frames.json",
+    );
+}